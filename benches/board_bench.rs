@@ -0,0 +1,81 @@
+//! Baselines for the core board operations, each reported as elements per second
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use expanded_ttt::{Board, Player};
+
+fn construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("new");
+    for dimension in 1..=6 {
+        group.throughput(Throughput::Elements(1));
+        group.bench_with_input(BenchmarkId::from_parameter(dimension), &dimension, |b, dimension| {
+            b.iter(|| Board::new(black_box(*dimension)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn get_4d(c: &mut Criterion) {
+    let mut board = Board::new(4).unwrap();
+    let positions = board.positions().collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("4d cells");
+    group.throughput(Throughput::Elements(positions.len() as u64));
+    group.bench_function("get", |b| b.iter(|| {
+        for pos in &positions {
+            black_box(board.get(black_box(pos)).unwrap());
+        }
+    }));
+    group.bench_function("get_mut", |b| b.iter(|| {
+        for pos in &positions {
+            *board.get_mut(black_box(pos)).unwrap() = 1;
+        }
+    }));
+    group.finish();
+}
+
+fn is_win_at_3d(c: &mut Criterion) {
+    // a lone piece in the center, so every direction is checked without finding a win
+    let mut board = Board::new(3).unwrap();
+    board.place_piece(Player::P1, &[1,1,1]).unwrap();
+
+    let mut group = c.benchmark_group("is_win_at");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("3d no win", |b| b.iter(|| board.is_win_at(black_box(&[1,1,1])).unwrap()));
+    group.finish();
+}
+
+fn place_piece_nearly_full(c: &mut Criterion) {
+    let mut board = Board::new(3).unwrap();
+    for (i, val) in board.data.iter_mut().enumerate() {
+        *val = if i % 2 == 0 { 1 } else { 2 };
+    }
+    let last = [1,1,1];
+    *board.get_mut(&last).unwrap() = 0;
+
+    let mut group = c.benchmark_group("place_piece");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("3d last cell", |b| b.iter_batched(
+        || board.clone(),
+        |mut board| board.place_piece(Player::P1, black_box(&last)).unwrap(),
+        BatchSize::SmallInput,
+    ));
+    group.finish();
+}
+
+fn legal_moves(c: &mut Criterion) {
+    let mut group = c.benchmark_group("legal_moves");
+    for dimension in [2, 3, 4] {
+        let board = Board::new(dimension).unwrap();
+
+        group.throughput(Throughput::Elements(board.data.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(dimension), &board, |b, board| {
+            b.iter(|| board.legal_moves(1))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, construction, get_4d, is_win_at_3d, place_piece_nearly_full, legal_moves);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+//! Win detection on a 5D board, run with and without `--features cached-directions` to compare
+//! recalculating the direction vectors on every call against looking them up
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use expanded_ttt::{Board, Player};
+
+fn is_win_at_5d(c: &mut Criterion) {
+    let mut board = Board::new(5).unwrap();
+    board.place_piece(Player::P1, &[1,1,0,0,0]).unwrap();
+
+    c.bench_function("is_win_at 5d x100", |b| b.iter(|| {
+        for _ in 0..100 {
+            black_box(board.is_win_at(black_box(&[1,1,0,0,0])).unwrap());
+        }
+    }));
+}
+
+fn directions_5d(c: &mut Criterion) {
+    c.bench_function("all_directions 5d", |b| b.iter(|| Board::all_directions(black_box(5))));
+
+    #[cfg(feature = "cached-directions")]
+    c.bench_function("all_directions_cached 5d", |b| b.iter(|| Board::all_directions_cached(black_box(5))));
+}
+
+criterion_group!(benches, is_win_at_5d, directions_5d);
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+//! Checking every direction through a position on a long 3D board, the scalar check in
+//! `is_win_at` against `check_win_dir_simd`. Run with `--features simd`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use expanded_ttt::Board;
+
+fn line_check(c: &mut Criterion) {
+    let mut board = Board::new_with_win_length(3, 16, 12);
+    for val in board.data.iter_mut() {
+        *val = 1;
+    }
+    let pos = [8, 8, 8];
+    let directions = Board::all_directions(3);
+
+    c.bench_function("scalar is_win_at 3d 16", |b| b.iter(|| {
+        black_box(board.is_win_at(black_box(&pos)).unwrap())
+    }));
+
+    c.bench_function("simd every direction 3d 16", |b| b.iter(|| {
+        directions.iter().any(|dir| board.check_win_dir_simd(black_box(&pos), dir).unwrap())
+    }));
+}
+
+criterion_group!(benches, line_check);
+criterion_main!(benches);
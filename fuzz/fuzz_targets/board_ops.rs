@@ -0,0 +1,42 @@
+//! Plays arbitrary moves on a small board, checking nothing panics. The first byte picks the
+//! dimension (1 to 4) and the second whether there is gravity, then every `dimension + 1` bytes
+//! are a player and a position. Coordinates go up to the side length, so out of bounds positions
+//! are tried too.
+//!
+//! Run from the repository root with `cargo +nightly fuzz run board_ops`.
+
+#![no_main]
+
+use expanded_ttt::{Board, Player, WinResult};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let [dimension, gravity, moves @ ..] = data else {
+        return;
+    };
+    let dimension = u16::from(dimension % 4 + 1);
+
+    let mut board = if gravity % 2 == 1 {
+        Board::new_with_gravity(dimension, usize::from(dimension) - 1)
+    } else {
+        Board::new(dimension).unwrap()
+    };
+
+    for chunk in moves.chunks_exact(usize::from(dimension) + 1) {
+        let (player, pos) = chunk.split_first().unwrap();
+        let pos: Vec<u8> = pos.iter().map(|val| val % (board.size() + 1)).collect();
+
+        // 0 isn't a player, so those bytes only check the position
+        if let Some(player) = Player::new(*player)
+            && board.place_piece(player, &pos).is_ok()
+        {
+            assert_eq!(board.get(&pos), Ok(Some(player)));
+        }
+        if let Ok(WinResult::Win { line, .. }) = board.is_win_at(&pos) {
+            assert!(line.contains(&pos));
+        }
+        _ = board.get(&pos);
+    }
+
+    assert_eq!(board.validate_invariants(), Ok(()));
+});
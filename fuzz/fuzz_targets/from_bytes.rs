@@ -0,0 +1,15 @@
+//! Reads boards from arbitrary bytes. Anything `Board::from_bytes` accepts has to be written back
+//! out as the same bytes.
+//!
+//! Run from the repository root with `cargo +nightly fuzz run from_bytes`.
+
+#![no_main]
+
+use expanded_ttt::Board;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(board) = Board::from_bytes(data) {
+        assert_eq!(board.to_bytes(), data);
+    }
+});
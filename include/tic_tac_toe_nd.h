@@ -0,0 +1,41 @@
+/* C interface to expanded-ttt boards, see src/ffi.rs. Link against the cdylib built by
+ * `cargo build --release` (target/release/libexpanded_ttt.so on Linux). */
+#ifndef TIC_TAC_TOE_ND_H
+#define TIC_TAC_TOE_ND_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* status codes returned by board_place_piece and board_get */
+#define FFI_OK 0
+#define FFI_NULL_POINTER (-1)
+#define FFI_OUT_OF_DIMENSION (-2)
+#define FFI_OUT_OF_BOUNDS (-3)
+#define FFI_OCCUPIED (-4)
+#define FFI_UNSUPPORTED (-5)
+#define FFI_INVALID_PIECE (-6)
+
+/* an opaque board, only ever used through a pointer */
+typedef struct BoardHandle BoardHandle;
+
+/* a new classic sized board, or NULL if dimension is 0. Free it with board_free. */
+BoardHandle *board_new(uint16_t dimension);
+
+/* free a board from board_new, doing nothing for NULL */
+void board_free(BoardHandle *board);
+
+/* place a piece for player at the pos_len coordinates in pos */
+int32_t board_place_piece(BoardHandle *board, uint8_t player, const uint8_t *pos, size_t pos_len);
+
+/* write the value at the pos_len coordinates in pos to out, 0 for an empty cell */
+int32_t board_get(const BoardHandle *board, const uint8_t *pos, size_t pos_len, uint8_t *out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
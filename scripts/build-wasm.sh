@@ -0,0 +1,7 @@
+#!/bin/sh
+# Build the JavaScript package into pkg/ with wasm-pack. The computer players are left out, as
+# their random numbers need extra setup on the web. The bindings' tests run with
+# `wasm-pack test --node -- --no-default-features --features wasm`.
+set -e
+cd "$(dirname "$0")/.."
+wasm-pack build --target web --out-dir pkg -- --no-default-features --features wasm "$@"
@@ -0,0 +1,1003 @@
+//! Computer players that search the game tree for the best move
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use rand::seq::IndexedRandom;
+
+use crate::{Board, Game, GameResult};
+
+/// A quick move for `player` without searching: a winning move if there is one, otherwise a
+/// move blocking another player from winning next turn, otherwise any legal move at random.
+/// Returns `None` if there is nowhere left to place.
+pub fn suggest_move(board: &Board, player: u8, num_players: u8) -> Option<Vec<u8>> {
+    if let Some(pos) = board.immediate_winning_moves(player).into_iter().next() {
+        return Some(pos);
+    }
+
+    let blocking = (1..=num_players)
+        .filter(|opponent| *opponent != player)
+        .find_map(|opponent| board.blocking_moves(player, opponent).into_iter().next());
+    if blocking.is_some() {
+        return blocking;
+    }
+
+    board.legal_moves(player).choose(&mut rand::rng()).cloned()
+}
+
+/// Find the best move for `player` by looking ahead `depth` plies (moves by any player), assuming
+/// every other player plays against `player`. Returns an empty position if there is nowhere left
+/// to place.
+pub fn minimax(board: &Board, depth: u8, player: u8, num_players: u8) -> Vec<u8> {
+    // moves are tried out on a copy which is put back the way it was after each one
+    let mut board = board.clone();
+
+    let mut best: Option<(i32, Vec<u8>)> = None;
+    for pos in board.legal_moves(player) {
+        let score = score_move(&mut board, &pos, depth, player, player, num_players);
+
+        if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+            best = Some((score, pos));
+        }
+    }
+
+    best.map(|(_, pos)| pos).unwrap_or_default()
+}
+
+/// Score a move by `mover` from the point of view of `maximizer`: 1 if they win, -1 if they
+/// lose, and 0 for a draw or if nothing is decided within `depth` plies
+fn score_move(board: &mut Board, pos: &[u8], depth: u8, mover: u8, maximizer: u8, num_players: u8) -> i32 {
+    let state = board.place_piece_number(mover, pos).expect("legal moves can be placed");
+
+    let score = match state {
+        GameResult::Win(winner) if winner == maximizer => 1,
+        GameResult::Win(_) => -1,
+        GameResult::Loss(loser) if loser == maximizer => -1,
+        GameResult::Loss(_) => 1,
+        GameResult::Draw => 0,
+        GameResult::Ongoing if depth <= 1 => 0,
+        GameResult::Ongoing => {
+            let next = mover % num_players + 1;
+
+            let scores = board.legal_moves(next)
+                .iter()
+                .map(|pos| score_move(board, pos, depth - 1, next, maximizer, num_players))
+                .collect::<Vec<_>>()
+                .into_iter();
+
+            // everyone else is assumed to be playing against the maximizer
+            if next == maximizer { scores.max() } else { scores.min() }.unwrap_or(0)
+        },
+    };
+
+    board.undo_piece_unchecked(pos).expect("the move was just placed");
+
+    score
+}
+
+/// What a stored score says about the real score of a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundType {
+    /// The score is the real score
+    Exact,
+    /// The real score is at least the score, the search stopped early because it was too good
+    /// for the other side to allow
+    LowerBound,
+    /// The real score is at most the score
+    UpperBound,
+}
+
+/// The result of searching a position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranspositionEntry {
+    /// How many plies the position was searched to
+    pub depth: u8,
+    pub score: i32,
+    pub best_move: Option<Vec<u8>>,
+    pub flag: BoundType,
+}
+
+/// Positions that have already been searched, keyed by the zobrist hash of the board along with
+/// who is moving and who is maximizing, so a position reached by a different order of moves
+/// isn't searched again
+#[derive(Debug, Default)]
+pub struct TranspositionTable {
+    entries: HashMap<u64, TranspositionEntry>,
+}
+
+impl TranspositionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: u64) -> Option<&TranspositionEntry> {
+        self.entries.get(&key)
+    }
+
+    pub fn insert(&mut self, key: u64, entry: TranspositionEntry) {
+        self.entries.insert(key, entry);
+    }
+
+    /// The number of positions stored
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The key of a board with `mover` to move, searched for `maximizer`
+    fn key(board: &Board, mover: u8, maximizer: u8) -> u64 {
+        board.zobrist_hash()
+            ^ u64::from(mover).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ u64::from(maximizer).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+    }
+}
+
+/// The score of a won position in an [`alpha_beta`] search, beyond anything an [`Evaluator`]
+/// should return so that a forced win always beats a good looking position
+pub const WIN_SCORE: i32 = 1_000_000;
+
+/// A way of scoring a position that the search can't see the end of
+pub trait Evaluator {
+    /// How good the board is for `player`, higher is better
+    fn evaluate(&self, board: &Board, player: u8) -> i32;
+}
+
+/// Scores a position by the lines each player could still complete, see [`Board::board_score`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineScoreEvaluator;
+
+impl Evaluator for LineScoreEvaluator {
+    fn evaluate(&self, board: &Board, player: u8) -> i32 {
+        board.board_score(player)
+    }
+}
+
+/// Scores every position at random between -100 and 100, for checking that the search plays
+/// forced wins and blocks no matter how positions are scored
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomEvaluator;
+
+impl Evaluator for RandomEvaluator {
+    fn evaluate(&self, _board: &Board, _player: u8) -> i32 {
+        rand::rng().random_range(-100..=100)
+    }
+}
+
+impl Board {
+    /// Score the board for `player` with an evaluator
+    pub fn static_eval(&self, evaluator: &dyn Evaluator, player: u8) -> i32 {
+        evaluator.evaluate(self, player)
+    }
+}
+
+/// Settings and statistics shared across an [`alpha_beta`] search
+pub struct Search {
+    pub num_players: u8,
+    /// The number of positions visited so far
+    pub nodes: usize,
+    /// Positions already searched, if they should be remembered
+    pub table: Option<TranspositionTable>,
+    /// How to score positions at the end of the search depth, which otherwise score 0
+    pub evaluator: Option<Box<dyn Evaluator>>,
+    /// When to give up searching, if ever
+    pub deadline: Option<Instant>,
+    /// Whether the search gave up at the deadline, leaving its result meaningless
+    pub timed_out: bool,
+}
+
+impl Search {
+    pub fn new(num_players: u8) -> Self {
+        Self {
+            num_players,
+            nodes: 0,
+            table: None,
+            evaluator: None,
+            deadline: None,
+            timed_out: false,
+        }
+    }
+
+    /// A search that remembers the positions it has searched in a transposition table, which is
+    /// kept between searches
+    pub fn with_table(num_players: u8) -> Self {
+        Self {
+            table: Some(TranspositionTable::new()),
+            ..Self::new(num_players)
+        }
+    }
+
+    /// A search that scores the positions at the end of its depth with `evaluator`
+    pub fn with_evaluator(num_players: u8, evaluator: impl Evaluator + 'static) -> Self {
+        Self {
+            evaluator: Some(Box::new(evaluator)),
+            ..Self::new(num_players)
+        }
+    }
+}
+
+impl std::fmt::Debug for Search {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Search")
+            .field("num_players", &self.num_players)
+            .field("nodes", &self.nodes)
+            .field("table", &self.table)
+            .field("evaluator", &self.evaluator.as_ref().map(|_| "dyn Evaluator"))
+            .field("deadline", &self.deadline)
+            .field("timed_out", &self.timed_out)
+            .finish()
+    }
+}
+
+/// Search `depth` plies ahead for the best move for `player`, the player to move, skipping any
+/// line of play that can't beat one already found. Scores are from the point of view of the
+/// maximizing player: `player` if `maximizing` is set, otherwise the player before them, with
+/// every other player playing against the maximizer. A win scores [`WIN_SCORE`] and a loss its
+/// negative, while positions at the end of the depth are scored by the search's evaluator.
+/// Returns the score with the best move, or no move if `depth` is 0 or there is nowhere left to
+/// place.
+///
+/// The board is changed while searching, but is put back the way it was before returning. If the
+/// search has a [`TranspositionTable`], positions found in it aren't searched again and every
+/// position searched is added to it. If the search has a deadline and runs past it, it stops
+/// with `timed_out` set and nothing from the unfinished positions is added to the table.
+pub fn alpha_beta(
+    board: &mut Board,
+    depth: u8,
+    alpha: i32,
+    beta: i32,
+    maximizing: bool,
+    player: u8,
+    search: &mut Search,
+) -> (i32, Option<Vec<u8>>) {
+    let num_players = search.num_players;
+    let maximizer = if maximizing { player } else { (player + num_players - 2) % num_players + 1 };
+
+    search_alpha_beta(board, depth, alpha, beta, player, maximizer, search)
+}
+
+fn search_alpha_beta(
+    board: &mut Board,
+    depth: u8,
+    mut alpha: i32,
+    mut beta: i32,
+    mover: u8,
+    maximizer: u8,
+    search: &mut Search,
+) -> (i32, Option<Vec<u8>>) {
+    if search.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        search.timed_out = true;
+    }
+    if search.timed_out {
+        return (0, None);
+    }
+
+    if depth == 0 {
+        let score = search.evaluator.as_ref().map_or(0, |evaluator| board.static_eval(evaluator.as_ref(), maximizer));
+        return (score, None);
+    }
+
+    let key = TranspositionTable::key(board, mover, maximizer);
+    let (alpha_start, beta_start) = (alpha, beta);
+
+    // only a search at least as deep as this one can stand in for it
+    let entry = search.table.as_ref()
+        .and_then(|table| table.get(key))
+        .filter(|entry| entry.depth >= depth);
+    if let Some(entry) = entry {
+        match entry.flag {
+            BoundType::Exact => return (entry.score, entry.best_move.clone()),
+            BoundType::LowerBound => alpha = alpha.max(entry.score),
+            BoundType::UpperBound => beta = beta.min(entry.score),
+        }
+
+        if alpha >= beta {
+            return (entry.score, entry.best_move.clone());
+        }
+    }
+
+    let maximizing = mover == maximizer;
+    let next = mover % search.num_players + 1;
+
+    let mut best: Option<(i32, Vec<u8>)> = None;
+    for pos in board.legal_moves(mover) {
+        search.nodes += 1;
+
+        let score = match board.place_piece_number(mover, &pos).expect("legal moves can be placed") {
+            GameResult::Win(winner) if winner == maximizer => WIN_SCORE,
+            GameResult::Win(_) => -WIN_SCORE,
+            GameResult::Loss(loser) if loser == maximizer => -WIN_SCORE,
+            GameResult::Loss(_) => WIN_SCORE,
+            GameResult::Draw => 0,
+            GameResult::Ongoing => search_alpha_beta(board, depth - 1, alpha, beta, next, maximizer, search).0,
+        };
+
+        board.undo_piece_unchecked(&pos).expect("the move was just placed");
+        if search.timed_out {
+            return (0, None);
+        }
+
+        let improved = best.as_ref().is_none_or(|(best_score, _)| {
+            if maximizing { score > *best_score } else { score < *best_score }
+        });
+        if improved {
+            best = Some((score, pos));
+        }
+
+        // narrow the window, stopping once the other side already has a better option elsewhere
+        if maximizing {
+            alpha = alpha.max(score);
+        } else {
+            beta = beta.min(score);
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    let (score, best_move) = match best {
+        Some((score, pos)) => (score, Some(pos)),
+        None => (0, None),
+    };
+
+    if let Some(table) = &mut search.table {
+        // a score outside the window it was searched with is only a bound on the real score
+        let flag = if score <= alpha_start {
+            BoundType::UpperBound
+        } else if score >= beta_start {
+            BoundType::LowerBound
+        } else {
+            BoundType::Exact
+        };
+
+        table.insert(key, TranspositionEntry { depth, score, best_move: best_move.clone(), flag });
+    }
+
+    (score, best_move)
+}
+
+/// Search deeper and deeper for the best move for `player`, one ply at a time, until
+/// `time_limit` is up, returning the best move of the deepest search that finished. Positions are
+/// scored with the [`LineScoreEvaluator`] at the end of each depth, and a transposition table is
+/// kept from one depth to the next. Stops early once a forced win or loss is found. Falls back on
+/// any legal move if not even a search 1 ply deep finishes, and returns an empty position if
+/// there is nowhere left to place.
+pub fn iterative_deepening(game: &Game, player: u8, time_limit: Duration) -> Vec<u8> {
+    let mut board = game.board().clone();
+    let mut search = Search {
+        evaluator: Some(Box::new(LineScoreEvaluator)),
+        deadline: Some(Instant::now() + time_limit),
+        ..Search::with_table(game.num_players())
+    };
+
+    let mut best = board.legal_moves(player).into_iter().next().unwrap_or_default();
+    let max_depth = u8::try_from(board.count_empty_cells()).unwrap_or(u8::MAX);
+
+    for depth in 1..=max_depth {
+        let (score, pos) = alpha_beta(&mut board, depth, i32::MIN, i32::MAX, true, player, &mut search);
+        if search.timed_out {
+            break;
+        }
+
+        if let Some(pos) = pos {
+            best = pos;
+        }
+        // looking further ahead won't change a forced result
+        if score.abs() == WIN_SCORE {
+            break;
+        }
+    }
+
+    best
+}
+
+/// How much [`mcts_best_move`] favors trying moves it knows little about over ones that have
+/// done well so far, the usual UCB1 constant of sqrt(2)
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// A position in a Monte Carlo search tree, along with how the games played through it went
+#[derive(Debug, Clone)]
+pub struct MctsNode {
+    /// The zobrist hash of the board at this position
+    pub board_hash: u64,
+    /// The number of games played through this position
+    pub visits: u32,
+    /// The total result of those games for the player who moved into this position, 1 for each
+    /// win and 0.5 for each draw
+    pub score: f64,
+    pub children: Vec<MctsNode>,
+    /// The move that led here from the parent, `None` for the root
+    pub move_taken: Option<Vec<u8>>,
+    terminal: Option<GameResult>, // how the game ended if it is over at this position
+}
+
+impl MctsNode {
+    fn new(board: &Board, move_taken: Option<Vec<u8>>, terminal: Option<GameResult>) -> Self {
+        Self {
+            board_hash: board.zobrist_hash(),
+            visits: 0,
+            score: 0.0,
+            children: Vec::new(),
+            move_taken,
+            terminal,
+        }
+    }
+
+    /// The UCB1 value of a child of a node visited `parent_visits` times, trading off how well
+    /// the child has done against how rarely it has been tried
+    fn ucb1(&self, parent_visits: u32) -> f64 {
+        let visits = f64::from(self.visits);
+        self.score / visits + EXPLORATION * (f64::from(parent_visits).ln() / visits).sqrt()
+    }
+}
+
+/// What a finished game is worth to `player`: 1 for a win, 0.5 for a draw, and 0 for a loss
+fn reward(result: GameResult, player: u8) -> f64 {
+    match result {
+        GameResult::Win(winner) if winner == player => 1.0,
+        GameResult::Loss(loser) if loser != player => 1.0,
+        GameResult::Draw | GameResult::Ongoing => 0.5,
+        GameResult::Win(_) | GameResult::Loss(_) => 0.0,
+    }
+}
+
+/// Play random moves from the board until the game ends, starting with `mover`, and put the
+/// board back afterwards
+fn rollout(board: &mut Board, mut mover: u8, num_players: u8) -> GameResult {
+    let mut played = Vec::new();
+    let mut rng = rand::rng();
+
+    let result = loop {
+        let Some(pos) = board.legal_moves(mover).choose(&mut rng).cloned() else {
+            break GameResult::Draw;
+        };
+
+        let state = board.place_piece_number(mover, &pos).expect("legal moves can be placed");
+        played.push(pos);
+        if state != GameResult::Ongoing {
+            break state;
+        }
+
+        mover = mover % num_players + 1;
+    };
+
+    for pos in played.iter().rev() {
+        board.undo_piece_unchecked(pos).expect("the move was just placed");
+    }
+
+    result
+}
+
+/// Play random legal moves in a game until it ends, then take them all back again, leaving the
+/// game as it was. For Monte Carlo rollouts, where the same game is played out many times
+/// without copying its board. Returns how the game ended, or its result straight away if it was
+/// already over.
+pub fn simulate_random_playout(game: &mut Game, rng: &mut impl rand::Rng) -> GameResult {
+    // undoing a move keeps its position counted towards a repetition, which a playout mustn't
+    let repetitions = game.repetitions.clone();
+    let mut played = 0;
+
+    let result = loop {
+        if game.state() != GameResult::Ongoing {
+            break game.state();
+        }
+
+        let Some(pos) = game.board().legal_moves(game.current_player()).choose(rng).cloned() else {
+            break GameResult::Draw;
+        };
+
+        game.play(&pos).expect("legal moves can be played");
+        played += 1;
+    };
+
+    for _ in 0..played {
+        game.undo_move().expect("the move was just played");
+    }
+    game.repetitions = repetitions;
+
+    result
+}
+
+impl Board {
+    /// Play `n` two player games at random on new hypercube boards, counting how each of them
+    /// ended. The same `seed` always plays the same games.
+    ///
+    /// # Panics
+    /// If `dimension` or `size` is 0, as there's no board to play on
+    pub fn simulate_n_games(dimension: u16, size: u8, n: u32, seed: u64) -> HashMap<GameResult, u32> {
+        assert!(dimension > 0 && size > 0, "Games need a board with at least 1 dimension and size");
+
+        let mut game = Game::with_board(Self::new_with_size(dimension, size), 2);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut counts = HashMap::new();
+
+        for _ in 0..n {
+            *counts.entry(simulate_random_playout(&mut game, &mut rng)).or_default() += 1;
+        }
+
+        counts
+    }
+
+    /// The best first move on an empty `size` sided hypercube, found by solving the two player
+    /// game after each of the kinds of first move: the center if there is one, a corner, and the
+    /// middle of an edge. The earliest of those is taken when they're as good as each other, so
+    /// the center wins ties. Every position after each move is searched, so this is only quick
+    /// on small boards.
+    pub fn optimal_first_move(dimension: u16, size: u8) -> Vec<u8> {
+        Self::new_with_size(dimension, size).best_first_move()
+    }
+
+    /// [`Board::optimal_first_move`] for an empty board of any shape and win length
+    fn best_first_move(&self) -> Vec<u8> {
+        let center = self.sizes().iter()
+            .all(|size| size % 2 == 1)
+            .then(|| self.sizes().iter().map(|size| size / 2).collect());
+        let corner = vec![0; self.sizes().len()];
+        let edge = self.sizes().iter().enumerate().map(|(axis, size)| if axis == 0 { size / 2 } else { 0 }).collect();
+
+        let mut candidates: Vec<Vec<u8>> = center.into_iter().chain([corner, edge]).collect();
+        candidates.dedup();
+
+        let mut board = self.clone();
+        let mut search = Search::with_table(2);
+        let mut best: Option<(i32, Vec<u8>)> = None;
+
+        for pos in candidates {
+            let score = match board.place_piece_number(1, &pos).expect("the board is empty") {
+                GameResult::Win(_) => WIN_SCORE,
+                GameResult::Ongoing => {
+                    let depth = u8::try_from(board.count_empty_cells()).unwrap_or(u8::MAX);
+                    // scores can't go past a win either way, so a found win cuts the search off
+                    alpha_beta(&mut board, depth, -WIN_SCORE, WIN_SCORE, false, 2, &mut search).0
+                },
+                _ => 0,
+            };
+            board.undo_piece_unchecked(&pos).expect("the move was just placed");
+
+            if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+                best = Some((score, pos));
+            }
+        }
+
+        best.map(|(_, pos)| pos).unwrap_or_default()
+    }
+}
+
+/// Play one game through the tree below `node`, with `mover` to move: go down through the most
+/// promising children, add a child for a move that hasn't been tried yet, play the game out at
+/// random, and record the result on the way back up
+fn mcts_iterate(node: &mut MctsNode, board: &mut Board, mover: u8, num_players: u8) -> GameResult {
+    let next = mover % num_players + 1;
+    let moved = (mover + num_players - 2) % num_players + 1;
+
+    let result = if let Some(result) = node.terminal {
+        result
+    } else {
+        let untried: Vec<_> = board.legal_moves(mover)
+            .into_iter()
+            .filter(|pos| node.children.iter().all(|child| child.move_taken.as_ref() != Some(pos)))
+            .collect();
+
+        if let Some(pos) = untried.choose(&mut rand::rng()) {
+            // expand the tree with a new move, played out at random
+            let state = board.place_piece_number(mover, pos).expect("legal moves can be placed");
+            let terminal = (state != GameResult::Ongoing).then_some(state);
+            let mut child = MctsNode::new(board, Some(pos.clone()), terminal);
+
+            let result = terminal.unwrap_or_else(|| rollout(board, next, num_players));
+            board.undo_piece_unchecked(pos).expect("the move was just placed");
+
+            child.visits = 1;
+            child.score = reward(result, mover);
+            node.children.push(child);
+
+            result
+        } else if let Some(child) = node.children.iter_mut().max_by(|a, b| a.ucb1(node.visits).total_cmp(&b.ucb1(node.visits))) {
+            let pos = child.move_taken.clone().expect("children have a move");
+
+            board.place_piece_number(mover, &pos).expect("moves in the tree can be placed");
+            let result = mcts_iterate(child, board, next, num_players);
+            board.undo_piece_unchecked(&pos).expect("the move was just placed");
+
+            result
+        } else {
+            // nowhere left to place
+            GameResult::Draw
+        }
+    };
+
+    node.visits += 1;
+    node.score += reward(result, moved);
+
+    result
+}
+
+/// Find a good move for `player` by playing `iterations` random games from the current position,
+/// steering more of them down the moves that have done well so far (Monte Carlo tree search with
+/// UCB1). Scales to big boards better than a full search. Returns the most played move, or an
+/// empty position if there is nowhere left to place.
+pub fn mcts_best_move(game: &Game, iterations: u32, player: u8) -> Vec<u8> {
+    let num_players = game.num_players();
+    let mut board = game.board().clone();
+    let mut root = MctsNode::new(&board, None, None);
+
+    for _ in 0..iterations {
+        mcts_iterate(&mut root, &mut board, player, num_players);
+    }
+
+    root.children.into_iter()
+        .max_by_key(|child| child.visits)
+        .and_then(|child| child.move_taken)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn minimax_wins() {
+        let mut board = Board::new(2).unwrap();
+        let expected = vec![2,0];
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,0]).unwrap();
+        board.place_piece(Player::P2, &[0,1]).unwrap();
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+
+        let actual = minimax(&board, 1, 1, 2);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn minimax_blocks() {
+        let mut board = Board::new(2).unwrap();
+        let expected = vec![2,1];
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,1]).unwrap();
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+
+        let actual = minimax(&board, 2, 1, 2);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn minimax_full_board() {
+        let mut board = Board::new(1).unwrap();
+
+        for (player, pos) in [(1,[0]),(2,[1]),(1,[2])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        assert!(minimax(&board, 9, 2, 2).is_empty());
+    }
+
+    /// Play every possible game from the given openings against minimax moving second, failing
+    /// if any of them is lost
+    fn never_loses(board: &mut Board, openings: &[Vec<u8>]) {
+        for pos in openings {
+            match board.place_piece(Player::P1, pos).unwrap() {
+                GameResult::Win(_) | GameResult::Loss(_) => panic!("minimax lost with the board at\n{board}"),
+                GameResult::Draw => {},
+                GameResult::Ongoing => {
+                    let reply = minimax(board, 9, 2, 2);
+
+                    if board.place_piece(Player::P2, &reply).unwrap() == GameResult::Ongoing {
+                        never_loses(board, &board.legal_moves(1));
+                    }
+
+                    board.undo_piece_unchecked(&reply).unwrap();
+                },
+            }
+
+            board.undo_piece_unchecked(pos).unwrap();
+        }
+    }
+
+    #[test]
+    fn minimax_never_loses() {
+        // every other opening is a rotation or reflection of a corner, an edge, or the center
+        never_loses(&mut Board::new(2).unwrap(), &[vec![0,0], vec![1,0], vec![1,1]]);
+    }
+
+    /// The number of positions in the full game tree below the board, up to `depth` plies
+    fn tree_size(board: &mut Board, depth: u8, player: u8) -> usize {
+        if depth == 0 {
+            return 0;
+        }
+
+        let mut nodes = 0;
+        for pos in board.legal_moves(player) {
+            nodes += 1;
+            if board.place_piece_number(player, &pos).unwrap() == GameResult::Ongoing {
+                nodes += tree_size(board, depth - 1, player % 2 + 1);
+            }
+            board.undo_piece_unchecked(&pos).unwrap();
+        }
+
+        nodes
+    }
+
+    #[test]
+    fn alpha_beta_matches_minimax() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+
+        for player in [1, 2] {
+            let expected = minimax(&board, 9, player, 2);
+
+            let mut search = Search::new(2);
+            let (_, actual) = alpha_beta(&mut board, 9, i32::MIN, i32::MAX, true, player, &mut search);
+
+            assert_eq!(actual, Some(expected));
+        }
+    }
+
+    #[test]
+    fn alpha_beta_fewer_nodes() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+        let full = tree_size(&mut board, 9, 2);
+
+        let mut search = Search::new(2);
+        let (score, _) = alpha_beta(&mut board, 9, i32::MIN, i32::MAX, true, 2, &mut search);
+
+        assert_eq!(score, 0); // perfect play from here is a draw
+        assert!(search.nodes * 10 < full, "visited {} of {} nodes", search.nodes, full);
+        assert_eq!(board.legal_moves(2).len(), 8); // the board is left as it was
+    }
+
+    #[test]
+    fn alpha_beta_no_depth() {
+        let mut board = Board::new(2).unwrap();
+        let mut search = Search::new(2);
+
+        let actual = alpha_beta(&mut board, 0, i32::MIN, i32::MAX, true, 1, &mut search);
+
+        assert_eq!(actual, (0, None));
+        assert_eq!(search.nodes, 0);
+    }
+
+    #[test]
+    fn transposition_table_fewer_nodes() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+
+        let mut plain = Search::new(2);
+        let expected = alpha_beta(&mut board, 9, i32::MIN, i32::MAX, true, 2, &mut plain);
+
+        let mut search = Search::with_table(2);
+        let actual = alpha_beta(&mut board, 9, i32::MIN, i32::MAX, true, 2, &mut search);
+
+        assert_eq!(actual.0, expected.0);
+        assert!(search.nodes < plain.nodes, "visited {} nodes, {} without a table", search.nodes, plain.nodes);
+        assert!(!search.table.as_ref().unwrap().is_empty());
+
+        // searching the same position again comes straight out of the table
+        search.nodes = 0;
+        let again = alpha_beta(&mut board, 9, i32::MIN, i32::MAX, true, 2, &mut search);
+
+        assert_eq!(again, actual);
+        assert_eq!(search.nodes, 0);
+    }
+
+    #[test]
+    fn suggest_move_wins_before_blocking() {
+        let mut board = Board::new(2).unwrap();
+        for (player, pos) in [(1,[0,0]), (1,[1,0]), (2,[0,1]), (2,[1,1])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        assert_eq!(suggest_move(&board, 1, 2), Some(vec![2,0]));
+        assert_eq!(suggest_move(&board, 2, 2), Some(vec![2,1]));
+    }
+
+    #[test]
+    fn suggest_move_blocks() {
+        let mut board = Board::new(2).unwrap();
+        for (player, pos) in [(1,[0,0]), (2,[0,1]), (2,[1,1])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        assert_eq!(suggest_move(&board, 1, 2), Some(vec![2,1]));
+    }
+
+    #[test]
+    fn suggest_move_random() {
+        let board = Board::new(2).unwrap();
+        assert!(suggest_move(&board, 1, 2).is_some_and(|pos| board.can_place_at(&pos)));
+
+        let mut full = Board::new(1).unwrap();
+        for (player, pos) in [(1,[0]), (2,[1]), (1,[2])] {
+            full.place_piece_number(player, &pos).unwrap();
+        }
+
+        assert_eq!(suggest_move(&full, 2, 2), None);
+    }
+
+    #[test]
+    fn line_score_evaluator_takes_center() {
+        let mut board = Board::new(2).unwrap();
+        let mut search = Search::with_evaluator(2, LineScoreEvaluator);
+
+        let (score, pos) = alpha_beta(&mut board, 1, i32::MIN, i32::MAX, true, 1, &mut search);
+
+        assert_eq!(pos, Some(vec![1,1]));
+        assert_eq!(score, 4); // the center is on 4 lines
+    }
+
+    #[test]
+    fn random_evaluator_still_wins_and_blocks() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,0]).unwrap();
+        board.place_piece(Player::P2, &[0,1]).unwrap();
+
+        for _ in 0..10 {
+            let mut search = Search::with_evaluator(2, RandomEvaluator);
+            let actual = alpha_beta(&mut board, 3, i32::MIN, i32::MAX, true, 1, &mut search);
+            assert_eq!(actual, (WIN_SCORE, Some(vec![2,0])));
+
+            let mut search = Search::with_evaluator(2, RandomEvaluator);
+            let (_, actual) = alpha_beta(&mut board, 2, i32::MIN, i32::MAX, true, 2, &mut search);
+            assert_eq!(actual, Some(vec![2,0]));
+        }
+
+        let score = board.static_eval(&RandomEvaluator, 1);
+        assert!((-100..=100).contains(&score));
+    }
+
+    #[test]
+    fn iterative_deepening_finds_forced_moves() {
+        // player 1 wins at [2,0], and player 2 has to block there
+        let mut game = Game::new(2, 2);
+        for pos in [[0,0], [0,1], [1,0], [1,1]] {
+            game.play(&pos).unwrap();
+        }
+        assert_eq!(iterative_deepening(&game, 1, Duration::from_secs(5)), vec![2,0]);
+
+        game.undo_move().unwrap();
+        assert_eq!(iterative_deepening(&game, 2, Duration::from_secs(5)), vec![2,0]);
+    }
+
+    #[test]
+    fn iterative_deepening_sees_a_fork() {
+        // X at two opposite corners and O in the center: O has to take an edge, as taking one of
+        // the last corners lets X fork a few moves later, past what a 1 ply search sees
+        let mut game = Game::new(2, 2);
+        for pos in [[0,0], [1,1], [2,2]] {
+            game.play(&pos).unwrap();
+        }
+
+        let shallow = {
+            let mut board = game.board().clone();
+            let mut search = Search::with_evaluator(2, LineScoreEvaluator);
+            alpha_beta(&mut board, 1, i32::MIN, i32::MAX, true, 2, &mut search).1.unwrap()
+        };
+        let deep = iterative_deepening(&game, 2, Duration::from_secs(5));
+
+        let corners = [vec![0,2], vec![2,0]];
+        assert!(corners.contains(&shallow), "a shallow search took {shallow:?}");
+        assert!(!corners.contains(&deep), "a deep search took {deep:?}");
+    }
+
+    #[test]
+    fn iterative_deepening_out_of_time() {
+        let game = Game::new(3, 2);
+        let actual = iterative_deepening(&game, 1, Duration::ZERO);
+        assert!(game.board().can_place_at(&actual));
+
+        let mut search = Search { deadline: Some(Instant::now()), ..Search::with_table(2) };
+        let mut board = game.board().clone();
+        assert_eq!(alpha_beta(&mut board, 3, i32::MIN, i32::MAX, true, 1, &mut search), (0, None));
+        assert!(search.timed_out);
+        assert!(search.table.unwrap().is_empty());
+    }
+
+    #[test]
+    fn random_playouts_match_theory() {
+        // under random play the first player wins 58.5% of classic games, the second 28.8%, and
+        // 12.7% are drawn
+        let mut game = Game::new(2, 2);
+        let mut rng = StdRng::seed_from_u64(318);
+        let mut counts = [0_u32; 3];
+
+        for _ in 0..1000 {
+            match simulate_random_playout(&mut game, &mut rng) {
+                GameResult::Win(player) => counts[usize::from(player) - 1] += 1,
+                GameResult::Draw => counts[2] += 1,
+                result => panic!("playout ended with {result:?}"),
+            }
+        }
+
+        assert_eq!(game.move_count(), 0); // every playout was taken back
+        for (count, expected) in counts.into_iter().zip([585, 288, 127]) {
+            assert!(count.abs_diff(expected) < 60, "got {counts:?} wins for each player and draws");
+        }
+    }
+
+    #[test]
+    fn random_playout_of_finished_game() {
+        let mut game = Game::new(1, 2);
+        for pos in [[0], [1], [2]] {
+            game.play(&pos).unwrap();
+        }
+
+        assert_eq!(simulate_random_playout(&mut game, &mut rand::rng()), GameResult::Draw);
+        assert_eq!(game.move_count(), 3);
+    }
+
+    #[test]
+    fn simulate_n_games() {
+        let counts = Board::simulate_n_games(2, 3, 500, 447);
+
+        assert_eq!(counts.values().sum::<u32>(), 500);
+        assert!(counts.keys().all(|result| matches!(result, GameResult::Win(1 | 2) | GameResult::Draw)));
+        assert_eq!(Board::simulate_n_games(2, 3, 500, 447), counts);
+
+        // one in a row wins straight away
+        assert_eq!(Board::simulate_n_games(3, 1, 20, 0), HashMap::from([(GameResult::Win(1), 20)]));
+        assert!(Board::simulate_n_games(2, 3, 0, 0).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 1 dimension and size")]
+    fn simulate_n_games_without_a_board() {
+        Board::simulate_n_games(0, 3, 1, 0);
+    }
+
+    #[test]
+    fn optimal_first_move() {
+        assert_eq!(Board::optimal_first_move(2, 3), vec![1,1]);
+
+        // every odd sized board quick enough to solve, 3x3x3 takes minutes
+        for (dimension, size) in [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1), (4, 1)] {
+            let center = vec![size / 2; usize::from(dimension)];
+            assert_eq!(Board::optimal_first_move(dimension, size), center, "{dimension}D with sides of {size}");
+        }
+    }
+
+    #[test]
+    fn best_first_move_searched() {
+        // 2 in a row on a line of 4: the corner is blocked straight away for a draw, but the
+        // middle of the edge makes two threats at once and wins, though it's tried after the corner
+        let board = Board::new_with_win_length(1, 4, 2);
+        assert_eq!(board.best_first_move(), vec![2]);
+    }
+
+    #[test]
+    fn mcts_takes_the_win() {
+        let mut game = Game::new(2, 2);
+        for pos in [[0,0], [0,1], [1,0], [1,1]] {
+            game.play(&pos).unwrap();
+        }
+
+        let actual = mcts_best_move(&game, 2000, 1);
+
+        assert_eq!(actual, vec![2,0]);
+    }
+
+    #[test]
+    fn mcts_game_over() {
+        let mut game = Game::new(1, 2);
+        for pos in [[0], [1], [2]] {
+            game.play(&pos).unwrap();
+        }
+
+        assert!(mcts_best_move(&game, 10, 2).is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+//! A C interface to boards, for calling the library from C or from Python through `ctypes`.
+//! Boards are handed out as opaque [`BoardHandle`] pointers, and every function taking a
+//! position returns one of the `FFI_*` status codes. `include/tic_tac_toe_nd.h` declares all of
+//! it for C.
+
+use std::slice;
+
+use crate::{Board, Error, IndexError, PlaceError};
+
+/// The call succeeded
+pub const FFI_OK: i32 = 0;
+/// A board or position pointer was null
+pub const FFI_NULL_POINTER: i32 = -1;
+/// The position had the wrong number of coordinates
+pub const FFI_OUT_OF_DIMENSION: i32 = -2;
+/// A coordinate was off the board
+pub const FFI_OUT_OF_BOUNDS: i32 = -3;
+/// The position already has a piece in it
+pub const FFI_OCCUPIED: i32 = -4;
+/// The position has nothing under it to rest on
+pub const FFI_UNSUPPORTED: i32 = -5;
+/// The player can't place pieces, i.e. it's 0
+pub const FFI_INVALID_PIECE: i32 = -6;
+
+/// A board owned by the caller, only ever seen from C as a pointer
+#[derive(Debug)]
+pub struct BoardHandle(Board);
+
+fn index_error_code(e: IndexError) -> i32 {
+    match e {
+        IndexError::OutOfDimension => FFI_OUT_OF_DIMENSION,
+        IndexError::OutOfBounds => FFI_OUT_OF_BOUNDS,
+    }
+}
+
+fn error_code(e: Error) -> i32 {
+    match e {
+        Error::IndexError(e) => index_error_code(e),
+        Error::PlaceError(PlaceError::Occupied) => FFI_OCCUPIED,
+        Error::PlaceError(PlaceError::Unsupported) => FFI_UNSUPPORTED,
+        Error::PlaceError(PlaceError::InvalidPiece) => FFI_INVALID_PIECE,
+        Error::PlaceError(PlaceError::GameOver) => unreachable!("a bare board has no game to be over"),
+        Error::BuildError(_) => unreachable!("placing a piece never builds a board"),
+    }
+}
+
+/// Borrow the position `pos` points to, with a null `pos` only allowed for an empty position
+///
+/// # Safety
+/// `pos` must be null or point to `pos_len` readable bytes
+unsafe fn position<'a>(pos: *const u8, pos_len: usize) -> Option<&'a [u8]> {
+    if pos.is_null() {
+        return (pos_len == 0).then_some(&[]);
+    }
+
+    // SAFETY: checked non-null above, the caller guarantees the length
+    Some(unsafe { slice::from_raw_parts(pos, pos_len) })
+}
+
+/// A new classic sized board, or null if `dimension` is 0. Free it with [`board_free`].
+#[unsafe(no_mangle)]
+pub extern "C" fn board_new(dimension: u16) -> *mut BoardHandle {
+    match Board::new(dimension) {
+        Ok(board) => Box::into_raw(Box::new(BoardHandle(board))),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Free a board made by [`board_new`]. Does nothing for null.
+///
+/// # Safety
+/// `board` must be null or come from [`board_new`], and not have been freed already
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn board_free(board: *mut BoardHandle) {
+    if !board.is_null() {
+        // SAFETY: the caller guarantees it came from Box::into_raw in board_new
+        drop(unsafe { Box::from_raw(board) });
+    }
+}
+
+/// Place a piece for `player` at the `pos_len` coordinates `pos` points to, returning
+/// [`FFI_OK`] or the code of what stopped it
+///
+/// # Safety
+/// `board` must be null or a live board from [`board_new`], and `pos` null or pointing to
+/// `pos_len` readable bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn board_place_piece(
+    board: *mut BoardHandle,
+    player: u8,
+    pos: *const u8,
+    pos_len: usize,
+) -> i32 {
+    // SAFETY: the caller guarantees both pointers are valid if they aren't null
+    let (Some(board), Some(pos)) = (unsafe { board.as_mut() }, unsafe { position(pos, pos_len) }) else {
+        return FFI_NULL_POINTER;
+    };
+
+    match board.0.place_piece_number(player, pos) {
+        Ok(_) => FFI_OK,
+        Err(e) => error_code(e),
+    }
+}
+
+/// Write the value at the `pos_len` coordinates `pos` points to into `out`, 0 for an empty
+/// cell, returning [`FFI_OK`] or the code of what stopped it. `out` is left alone on failure.
+///
+/// # Safety
+/// `board` must be null or a live board from [`board_new`], `pos` null or pointing to
+/// `pos_len` readable bytes, and `out` null or writable
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn board_get(
+    board: *const BoardHandle,
+    pos: *const u8,
+    pos_len: usize,
+    out: *mut u8,
+) -> i32 {
+    // SAFETY: the caller guarantees all the pointers are valid if they aren't null
+    let (Some(board), Some(pos), Some(out)) =
+        (unsafe { board.as_ref() }, unsafe { position(pos, pos_len) }, unsafe { out.as_mut() })
+    else {
+        return FFI_NULL_POINTER;
+    };
+
+    match board.0.get(pos) {
+        Ok(val) => {
+            *out = val.map_or(0, u8::from);
+            FFI_OK
+        },
+        Err(e) => index_error_code(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn place_and_get() {
+        let board = board_new(2);
+        let pos = [1, 2];
+        let mut out = 0;
+
+        unsafe {
+            assert_eq!(board_place_piece(board, 1, pos.as_ptr(), pos.len()), FFI_OK);
+            assert_eq!(board_get(board, pos.as_ptr(), pos.len(), &mut out), FFI_OK);
+            assert_eq!(out, 1);
+
+            assert_eq!(board_place_piece(board, 2, pos.as_ptr(), pos.len()), FFI_OCCUPIED);
+            assert_eq!(board_place_piece(board, 0, [0, 0].as_ptr(), 2), FFI_INVALID_PIECE);
+
+            board_free(board);
+        }
+    }
+
+    #[test]
+    fn error_codes() {
+        assert!(board_new(0).is_null());
+
+        let board = board_new(2);
+        let mut out = 7;
+
+        unsafe {
+            assert_eq!(board_get(board, [0].as_ptr(), 1, &mut out), FFI_OUT_OF_DIMENSION);
+            assert_eq!(board_get(board, [0, 3].as_ptr(), 2, &mut out), FFI_OUT_OF_BOUNDS);
+            assert_eq!(out, 7);
+
+            assert_eq!(board_get(std::ptr::null(), [0, 0].as_ptr(), 2, &mut out), FFI_NULL_POINTER);
+            assert_eq!(board_get(board, std::ptr::null(), 2, &mut out), FFI_NULL_POINTER);
+            assert_eq!(board_get(board, [0, 0].as_ptr(), 2, std::ptr::null_mut()), FFI_NULL_POINTER);
+            assert_eq!(board_place_piece(std::ptr::null_mut(), 1, [0, 0].as_ptr(), 2), FFI_NULL_POINTER);
+
+            board_free(board);
+            board_free(std::ptr::null_mut());
+        }
+    }
+}
@@ -1,153 +1,550 @@
-use std::alloc::{self, Layout};
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU8;
+use std::ops::ControlFlow;
 
-#[derive(Debug)]
+#[cfg(feature = "ai")]
+pub mod ai;
+pub mod ffi;
+#[cfg(feature = "network")]
+pub mod network;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
 pub enum IndexError {
+    #[error("not enough or too few dimensions given in position slice")]
     OutOfDimension,
+    #[error("given position exceeds bounds of board")]
     OutOfBounds,
 }
 
-impl std::fmt::Display for IndexError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::OutOfBounds => write!(f, "given position exceeds bounds of board"),
-            Self::OutOfDimension => write!(f, "not enough or too few dimensions given in position slice"),
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum PlaceError {
+    #[error("position is not supported by previous pieces")]
+    Unsupported,
+    #[error("position is already occupied")]
+    Occupied,
+    #[error("piece cannot be placed by this player")]
+    InvalidPiece,
+    #[error("the game is already over")]
+    GameOver,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("Placement error: {0}")]
+    PlaceError(#[from] PlaceError),
+    #[error("Index error: {0}")]
+    IndexError(#[from] IndexError),
+    /// There's no board to play on, e.g. when replaying a [`GameRecord`] of an impossible board
+    #[error("Build error: {0}")]
+    BuildError(#[from] BuildError),
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum UndoError {
+    #[error("no moves have been played")]
+    NothingToUndo,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum BuildError {
+    #[error("board needs at least 1 dimension")]
+    ZeroDimension,
+    #[error("board size must be at least 1")]
+    ZeroSize,
+    #[error("win length must be between 1 and the board size")]
+    InvalidWinLength,
+    #[error("gravity axis must be one of the board's dimensions")]
+    InvalidGravityAxis,
+    /// The board has more cells than can be counted or allocated
+    #[error("board is too large to fit in memory")]
+    TooLarge,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DeserializeError {
+    #[error("bytes end before the board header does")]
+    Truncated,
+    #[error("board header describes an impossible board")]
+    InvalidHeader,
+    #[error("number of cells doesn't match the board header")]
+    WrongDataLength,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ParseError {
+    #[error("Failed to parse as u8: {0}")]
+    InvalidNumber(#[from] std::num::ParseIntError),
+    #[error("Index error: {0}")]
+    IndexError(#[from] IndexError),
+    /// The text isn't laid out the way the notation expects, e.g. a missing header line
+    #[error("text is not in the expected notation")]
+    InvalidFormat,
+}
+
+/// A vector pointing from a cell to one of its neighbors, each component is -1, 0, or 1
+pub type Direction = Vec<i8>;
+
+/// The coordinates of a cell, checked to be on a board of a given dimension and size. A
+/// `&Position` can be passed anywhere a `&[u8]` position is taken, such as [`Board::get`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Position(Vec<u8>);
+
+impl Position {
+    /// Check that `coords` is a position on a hypercube board with sides of length `size`
+    pub fn new(coords: Vec<u8>, dimension: u16, size: u8) -> Result<Self, IndexError> {
+        if coords.len() != usize::from(dimension) {
+            return Err(IndexError::OutOfDimension);
+        }
+
+        if coords.iter().any(|val| *val >= size) {
+            return Err(IndexError::OutOfBounds);
         }
+
+        Ok(Self(coords))
+    }
+
+    /// Parse a position written as numbers separated by commas and/or spaces, e.g. `1, 2, 3`,
+    /// and check it is on a hypercube board with sides of length `size`
+    pub fn from_str(s: &str, dimension: u16, size: u8) -> Result<Self, ParseError> {
+        let pos: Self = s.parse()?;
+        Ok(Self::new(pos.0, dimension, size)?)
+    }
+
+    pub fn coords(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The position written as comma separated numbers, e.g. `1,2,3`, which can be parsed back
+    pub fn to_notation(&self) -> String {
+        self.0.iter().map(u8::to_string).collect::<Vec<_>>().join(",")
     }
 }
 
-impl std::error::Error for IndexError {}
+/// Parses numbers separated by commas and/or spaces without checking them against a board, see
+/// [`Position::from_str`] for that
+impl std::str::FromStr for Position {
+    type Err = ParseError;
 
-#[derive(Debug)]
-pub enum PlaceError {
-    Unsupported,
-    Occupied,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let coords = s.trim()
+            .split([' ', ','])
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<u8>())
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self(coords))
+    }
 }
 
-impl std::fmt::Display for PlaceError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Unsupported => write!(f, "position is not supported by previous pieces"),
-            Self::Occupied => write!(f, "position is already occupied"),
-        }
+impl std::ops::Deref for Position {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
 }
 
-impl std::error::Error for PlaceError {}
+impl AsRef<[u8]> for Position {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
 
-#[derive(Debug)]
-pub enum Error {
-    PlaceError(PlaceError),
-    IndexError(IndexError),
+impl From<Position> for Vec<u8> {
+    fn from(value: Position) -> Self {
+        value.0
+    }
 }
 
-impl std::fmt::Display for Error {
+impl std::fmt::Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::PlaceError(e) => write!(f, "Placement error: {}", e),
-            Self::IndexError(e) => write!(f, "Index error: {}", e),
+        write!(f, "(")?;
+        for (i, val) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{val}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A player's piece, numbered from 1 as 0 is left for empty cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Player(NonZeroU8);
+
+impl Player {
+    pub const P1: Self = Self(NonZeroU8::new(1).unwrap());
+    pub const P2: Self = Self(NonZeroU8::new(2).unwrap());
+    pub const P3: Self = Self(NonZeroU8::new(3).unwrap());
+
+    /// The player numbered `number`, `None` for 0
+    pub const fn new(number: u8) -> Option<Self> {
+        match NonZeroU8::new(number) {
+            Some(number) => Some(Self(number)),
+            None => None,
         }
     }
+
+    pub const fn number(self) -> u8 {
+        self.0.get()
+    }
+}
+
+impl From<Player> for u8 {
+    fn from(value: Player) -> Self {
+        value.number()
+    }
 }
 
-impl From<IndexError> for Error {
-    fn from(value: IndexError) -> Self {
-        Self::IndexError(value)
+/// Fails with [`PlaceError::InvalidPiece`] for 0, the value of an empty cell
+impl TryFrom<u8> for Player {
+    type Error = PlaceError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(PlaceError::InvalidPiece)
     }
 }
 
-impl From<PlaceError> for Error {
-    fn from(value: PlaceError) -> Self {
-        Self::PlaceError(value)
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
-impl std::error::Error for Error {}
+/// Whether a position is part of a winning line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinResult {
+    NoWin,
+    /// `line` holds the positions of the winning line, in order from one end to the other
+    Win { player: u8, line: Vec<Vec<u8>> },
+}
 
-#[derive(Debug)]
-pub struct Board<'a> {
-    pub dimension: u8, // this dimension will be suitable for out-of-bounds checks as tic-tac-toe
-    // boards have sides with a known length (3), i.e. they are hypercubes
-    pub data: &'a mut [u8],
+/// A line that a player can win on with one more piece
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Threat {
+    pub player: u8,
+    /// The one empty cell in the line, which can be placed in right now
+    pub empty_pos: Vec<u8>,
+    pub line: Vec<Vec<u8>>,
+}
+
+/// The state of a game after a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameResult {
+    /// Nobody has won yet, play moves on to the next player
+    Ongoing,
+    /// The game is over and the carried player has won
+    Win(u8),
+    /// The board is full and nobody has won
+    Draw,
+    /// The game is over and the carried player has lost, by completing a line under misère rules
+    Loss(u8),
+}
+
+/// The rule set a game is played under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameMode {
+    /// Completing a line wins
+    #[default]
+    Standard,
+    /// Misère rules: completing a line loses. The board reports the line as a
+    /// [`GameResult::Loss`] for the player who completed it, everything else plays the same.
+    Misere,
+    /// Players may place any player's piece, completing a line of any piece wins
+    Wild,
 }
 
-impl Board<'_> {
-    const SIZE: u8 = 3; // the length of a tic-tac-toe board, also the number of pieces in a row
-    // to win
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub dimension: u16, // this dimension will be suitable for out-of-bounds checks as tic-tac-toe
+    // boards have sides with known lengths (sizes)
+    pub data: Vec<u8>,
+    sizes: Vec<u8>, // the length of the board along each dimension
+    strides: Vec<usize>, // the distance in data between neighbors along each dimension
+    win_length: u8, // the number of pieces in a row to win, for lines at least that long
+    win_lines: Option<Vec<Vec<Vec<u8>>>>, // every winning line, once precompute_wins is called
+    hash: u64, // the zobrist hash of the pieces placed and removed through the board's methods
+    mode: GameMode, // how a completed line is scored
+    gravity_axis: Option<usize>, // the axis pieces fall along, if any
+    // the number of pieces stacked in each column along the gravity axis, keyed by the position
+    // with the gravity axis left out. Empty columns aren't stored.
+    column_heights: HashMap<Vec<u8>, u8>,
+}
+
+impl Board {
+    const DEFAULT_SIZE: u8 = 3; // the length of a classic tic-tac-toe board
+
+    /// Create a classic sized hypercube board. Errors if `dimension` is 0, since a board with no
+    /// dimensions has a single cell and no lines through it, or if the board is too large to
+    /// allocate.
+    pub fn new(dimension: u16) -> Result<Self, BuildError> {
+        Self::try_new_rect(&vec![Self::DEFAULT_SIZE; dimension.into()])
+    }
+
+    /// Create a hypercube board with sides of length `size`, where `size` pieces in a row wins
+    pub fn new_with_size(dimension: u16, size: u8) -> Self {
+        Self::new_rect(&vec![size; dimension.into()])
+    }
+
+    /// Create a hypercube board with sides of length `size`, where `win_length` pieces in a row
+    /// wins
+    pub fn new_with_win_length(dimension: u16, size: u8, win_length: u8) -> Self {
+        assert!(win_length > 0 && win_length <= size, "Win length must be between 1 and the board size");
+
+        let mut board = Self::new_with_size(dimension, size);
+        board.win_length = win_length;
+        board
+    }
+
+    /// Create a classic sized hypercube board where pieces fall along `axis`, so a piece can only
+    /// be placed at the bottom of the axis or on top of another piece
+    pub fn new_with_gravity(dimension: u16, axis: usize) -> Self {
+        assert!(axis < dimension.into(), "Gravity axis must be one of the board's dimensions");
+
+        let mut board = Self::new_with_size(dimension, Self::DEFAULT_SIZE);
+        board.set_gravity_axis(Some(axis));
+        board
+    }
+
+    /// Create a board with the given length along each dimension. A line wins when it fills the
+    /// shortest side it runs along, e.g. 4 in a row along the long side of a 3x4 board but only 3
+    /// on its diagonals.
+    ///
+    /// # Panics
+    /// If there are no sizes, any size is 0, or the board is too large to allocate
+    pub fn new_rect(sizes: &[u8]) -> Self {
+        Self::try_new_rect(sizes).unwrap_or_else(|e| panic!("Can't create board: {e}"))
+    }
+
+    /// [`Board::new_rect`], erroring instead of panicking
+    fn try_new_rect(sizes: &[u8]) -> Result<Self, BuildError> {
+        if sizes.is_empty() {
+            return Err(BuildError::ZeroDimension);
+        }
+        if sizes.contains(&0) {
+            return Err(BuildError::ZeroSize);
+        }
+
+        let dimension = sizes.len().try_into().map_err(|_| BuildError::TooLarge)?;
+        let length = Self::get_data_length(sizes).ok_or(BuildError::TooLarge)?;
 
-    pub fn new(dimension: u8) -> Self {
-        let length = Self::get_data_length(dimension);
-        let layout = Self::get_layout(dimension);
+        // the length can be counted but not necessarily allocated
+        let mut data = Vec::new();
+        data.try_reserve_exact(length).map_err(|_| BuildError::TooLarge)?;
+        data.resize(length, 0);
 
-        let ptr = unsafe { alloc::alloc_zeroed(layout) };
-        let data = unsafe { std::slice::from_raw_parts_mut(ptr, length) };
+        // every stride is at most the length, so none of them overflow
+        let strides = sizes.iter()
+            .scan(1, |stride, size| {
+                let current = *stride;
+                *stride *= usize::from(*size);
+                Some(current)
+            })
+            .collect();
 
-        return Self {
+        Ok(Self {
             dimension,
             data,
+            sizes: Vec::from(sizes),
+            strides,
+            // by default a line has to be filled to win
+            win_length: sizes.iter().copied().max().unwrap_or(1),
+            win_lines: None,
+            hash: 0,
+            mode: GameMode::Standard,
+            gravity_axis: None,
+            column_heights: HashMap::new(),
+        })
+    }
+
+    /// A classic 2D board from its rows, top to bottom, so `rows[y][x]` is the cell at `[x, y]`
+    /// and the array reads the way the board is displayed. Cells are 0 for empty or a player.
+    pub fn from_2d_array(rows: [[u8; 3]; 3]) -> Self {
+        let data: Vec<u8> = rows.into_iter().flatten().collect();
+
+        Self::from_flat_slice(2, Self::DEFAULT_SIZE, &data).expect("3 rows of 3 fill a classic board")
+    }
+
+    /// A hypercube board with its cells copied from `data`, laid out as in [`Board::data`] with
+    /// the first axis changing fastest. Errors with `OutOfDimension` for a board without any
+    /// cells, or `OutOfBounds` if `data` isn't exactly one value per cell. The cells aren't
+    /// checked against the rules, see [`Board::validate_invariants`] for that.
+    pub fn from_flat_slice(dimension: u16, size: u8, data: &[u8]) -> Result<Self, IndexError> {
+        if dimension == 0 || size == 0 {
+            return Err(IndexError::OutOfDimension);
+        }
+        if Self::get_data_length(&vec![size; dimension.into()]) != Some(data.len()) {
+            return Err(IndexError::OutOfBounds);
         }
+
+        let mut board = Self::new_with_size(dimension, size);
+        board.data.copy_from_slice(data);
+        board.recompute_zobrist_hash();
+
+        Ok(board)
     }
 
-    fn get_data_length(dimension: u8) -> usize {
-        usize::from(Self::SIZE).pow(dimension.into()) // length of 3 along each dimension, board is a hypercube
+    /// The length of the board along each dimension, or of its shortest side if the board isn't a
+    /// hypercube
+    pub fn size(&self) -> u8 {
+        self.sizes.iter().copied().min().unwrap_or(1)
     }
 
+    /// The length of the board along each dimension
+    pub fn sizes(&self) -> &[u8] {
+        &self.sizes
+    }
 
-    fn get_layout(dimension: u8) -> Layout {
-        let length = Self::get_data_length(dimension);
-        Layout::array::<u8>(length).expect("Board dimension too large")
+    /// The number of pieces in a row needed to win, or to fill a line that is shorter than that
+    pub fn win_length(&self) -> u8 {
+        self.win_length
     }
 
-    pub fn get_mut(&mut self, pos: &[u8]) -> Result<&mut u8, IndexError> {
-        if pos.len() != self.dimension.into() {
-            return Err(IndexError::OutOfDimension); // error here 
-        }
+    /// The rules a completed line is scored under
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
 
-        let mut index: usize = 0;
-        for (i, val) in pos.iter().enumerate() {
-            if *val > Self::SIZE {
-                return Err(IndexError::OutOfBounds);
+    /// Change the rules a completed line is scored under, e.g. to [`GameMode::Misere`] so that
+    /// completing a line loses
+    pub fn set_mode(&mut self, mode: GameMode) {
+        self.mode = mode;
+    }
+
+    /// The axis pieces fall along, or `None` if they stay where they are placed
+    pub fn gravity_axis(&self) -> Option<usize> {
+        self.gravity_axis
+    }
+
+    /// Change the axis pieces fall along, or turn gravity off with `None`. Pieces already on the
+    /// board aren't moved.
+    pub fn set_gravity_axis(&mut self, axis: Option<usize>) {
+        assert!(axis.is_none_or(|axis| axis < self.sizes.len()), "Gravity axis must be one of the board's dimensions");
+        self.gravity_axis = axis;
+        self.recompute_column_heights();
+    }
+
+    /// The column a position is in along the gravity axis, i.e. the position without its
+    /// coordinate along the axis
+    fn column_of(pos: &[u8], axis: usize) -> Vec<u8> {
+        let mut column = Vec::from(pos);
+        column.remove(axis);
+        column
+    }
+
+    /// Count up the stack in every column from scratch, for when the column heights can't be kept
+    /// up to date move by move
+    fn recompute_column_heights(&mut self) {
+        self.column_heights.clear();
+        let Some(axis) = self.gravity_axis else {
+            return;
+        };
+
+        for index in 0..self.data.len() {
+            let mut pos = self.position_of(index);
+            if pos[axis] != 0 {
+                continue;
+            }
+
+            let column = Self::column_of(&pos, axis);
+            while pos[axis] < self.sizes[axis] && self.cell(&pos).is_ok_and(|val| val != 0) {
+                pos[axis] += 1;
             }
 
-            index += usize::from(Self::SIZE).pow(i.try_into().unwrap()) * usize::from(*val);
+            if pos[axis] > 0 {
+                self.column_heights.insert(column, pos[axis]);
+            }
         }
+    }
 
-        Ok(self.data.get_mut(index).unwrap())
+    /// Iterate over every position on the board
+    pub fn positions(&self) -> PositionIter<'_> {
+        PositionIter {
+            sizes: &self.sizes,
+            next: Some(vec![0; self.sizes.len()]),
+        }
     }
 
-    /// Get the value at a position
-    pub fn get(&self, pos: &[u8]) -> Result<u8, IndexError> {
-        if pos.len() != self.dimension.into() {
+    /// Iterate over every position with a piece in it
+    pub fn occupied_positions(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.positions().filter(|pos| self.cell(pos).is_ok_and(|val| val != 0))
+    }
+
+    /// Iterate over every position without a piece in it
+    pub fn empty_positions(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.positions_of_player(0)
+    }
+
+    /// Iterate over every position holding one of a player's pieces
+    pub fn positions_of_player(&self, player: u8) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.positions().filter(move |pos| self.cell(pos) == Ok(player))
+    }
+
+    /// The number of bytes the board's cells take up
+    pub fn memory_usage(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The number of bytes the cells of a hypercube board would take up, without making it. Too
+    /// big to fit in a `usize` comes out as `usize::MAX`.
+    pub fn projected_memory_usage(dimension: u16, size: u8) -> usize {
+        usize::from(size).saturating_pow(dimension.into())
+    }
+
+    fn get_data_length(sizes: &[u8]) -> Option<usize> {
+        sizes.iter().try_fold(1_usize, |length, size| length.checked_mul(usize::from(*size)))
+    }
+
+    /// The index into `data` of a position
+    fn index_of(&self, pos: &[u8]) -> Result<usize, IndexError> {
+        if pos.len() != usize::from(self.dimension) {
             return Err(IndexError::OutOfDimension); // error here 
         }
 
         let mut index: usize = 0;
-        for (i, val) in pos.iter().enumerate() {
-            if *val > Self::SIZE {
+        for ((val, size), stride) in pos.iter().zip(&self.sizes).zip(&self.strides) {
+            if val >= size {
                 return Err(IndexError::OutOfBounds);
             }
 
             // index each dimension by adding its offset from 0
-            index += usize::from(Self::SIZE).pow(i.try_into().unwrap()) * usize::from(*val);
+            index += stride * usize::from(*val);
         }
 
+        Ok(index)
+    }
+
+    pub fn get_mut(&mut self, pos: &[u8]) -> Result<&mut u8, IndexError> {
+        let index = self.index_of(pos)?;
+
+        Ok(self.data.get_mut(index).unwrap())
+    }
+
+    /// The player with a piece at a position, `None` if it's empty
+    pub fn get(&self, pos: &[u8]) -> Result<Option<Player>, IndexError> {
+        Ok(Player::new(self.cell(pos)?))
+    }
+
+    /// The raw value of the cell at a position, 0 if it's empty
+    fn cell(&self, pos: &[u8]) -> Result<u8, IndexError> {
+        let index = self.index_of(pos)?;
+
         Ok(self.data[index])
     }
 
     /// Place a piece on the board, taking into account gravity. Errors if position cannot be
-    /// indexed or position is unsupported. Otherwise returns ().
-    pub fn place_piece(&mut self, player: u8, position: &[u8]) -> Result<bool, Error> {
-        // find the highest non-zero dimension of the position
-        let highest = (position.len()-1) - position.iter().rev().position(|&e| e != 0).unwrap_or(position.len()-1);
-
-        // ignore support for placements on the original 2d board
-        if highest > 1 {
-            // get the position directly "below" this (i.e. the position that supports the current
-            // position)
-            let mut supporting_pos = Vec::from(position);
-            supporting_pos[highest] -= 1;
-
+    /// indexed or position is unsupported. Otherwise returns the state of the game after the
+    /// piece is placed, where a completed line is a loss instead of a win under misère rules.
+    pub fn place_piece(&mut self, player: Player, position: &[u8]) -> Result<GameResult, Error> {
+        if let Some(supporting_pos) = self.supporting_position(position) {
             // 0 == no piece there == no support for current position
-            if self.get(&supporting_pos)? == 0 {
+            if self.cell(&supporting_pos)? == 0 {
                 return Err(PlaceError::Unsupported.into());
             }
         }
@@ -159,280 +556,5483 @@ impl Board<'_> {
         }
 
         // place the piece
-        *val = player;
+        *val = player.into();
+        self.update_zobrist_hash(position, player.into());
+
+        if let Some(axis) = self.gravity_axis {
+            let height = self.column_heights.entry(Self::column_of(position, axis)).or_default();
+            *height = (*height).max(position[axis] + 1);
+        }
 
-        let win = self.is_win_at(position)?;
+        // only what placing and undoing pieces keep true, pieces can be placed in any order
+        debug_assert!(!self.has_structural_problem(), "invalid board state:\n{}", self.structural_problems().join("\n"));
 
-        return Ok(win);
+        self.game_result(position)
     }
 
-    /// Check to see if there is a win at the given position. Intended to be used directly after
-    /// placing a piece to detect a winning move. 
-    pub fn is_win_at(&self, pos: &[u8]) -> Result<bool, Error> {
-        // the key will be to just check_win_dir each directional vector from the position
-        //todo!("Woah! This part is way harder than expected!");
-        
-        // note: this entire loop may well be completely replaced with a precalculated list of
-        // vectors to that point to neighbors (but tic-tac-toe is not a very intensive task rn)
-
-        let len = pos.len();
+    /// [`Board::place_piece`] for a player given by number, as players are everywhere else,
+    /// failing with [`PlaceError::InvalidPiece`] for 0
+    pub(crate) fn place_piece_number(&mut self, player: u8, position: &[u8]) -> Result<GameResult, Error> {
+        self.place_piece(player.try_into()?, position)
+    }
 
-        // setup a direction vector that we'll use to calculate each neighbor direction
-        let mut dir: Vec<i8> = Vec::with_capacity(len);
-        unsafe {dir.set_len(len)};
-        dir.fill(-1);
-
-        while dir[0] <= 0 {
-            // skip the vector that points nowhere, else we'll always measure a win
-            // check to see if win and report back if it is
-            if !dir.iter().all(|n| *n==0) && self.check_win_dir(pos, &dir)? {
-                return Ok(true)
-            }
-
-            // add one at the last dimension
-            dir[len-1] += 1;
-
-            // propagate addition up dimensions
-            for i in (0..dir.len()).rev() {
-                // for any component that is beyond pointing to a neighbor |1| or 0
-                if dir[i] > 1 {
-                    // carry over to next component (checked on next iteration of for loop)
-                    dir[i-1] += 1;
-                    // reset this component
-                    dir[i] = -1;
-                }
-            }
+    /// The result of the game after a piece was placed at `last_pos`: a win for a line through
+    /// it, or a loss under misère rules, otherwise a draw if the board is full
+    pub fn game_result(&self, last_pos: &[u8]) -> Result<GameResult, Error> {
+        if let WinResult::Win { player, .. } = self.is_win_at(last_pos)? {
+            return Ok(match self.mode {
+                GameMode::Misere => GameResult::Loss(player),
+                _ => GameResult::Win(player),
+            });
         }
-        
-        // no win found
-        Ok(false)
-    }
-
-    /// Check for a win at a position along a given vector
-    fn check_win_dir(&self, pos: &[u8], dir: &[i8]) -> Result<bool, Error> {
-        // the key to doing this is realizing that the vector wraps at the edges of the board. For
-        // example, if you check along a 1d board: 
-        // ```
-        // for i in 0..3 {
-        //  if (pos+(i*dir)) %euclid 3 != player { return false };
-        // }
-        // ```
-        // This is because if you just add the directional vector you travel a certain path, now
-        // when you constrain it to the board it wraps around perfectly!
-        // pos = (0,0); dir = (1,-1); =>
-        // pos2 = (1,2); pos3 = (2,1);
-        // These positions lie on a line and it works out!
-        
-        if pos.len() != dir.len() {
-            return Err(IndexError::OutOfDimension.into());
+
+        if self.is_full() {
+            return Ok(GameResult::Draw);
         }
 
-        let dir = Vec::from(dir);
-        let mut pos = Vec::from(pos);
+        Ok(GameResult::Ongoing)
+    }
 
-        let player = self.get(&pos)?;
-        if player == 0 {
-            return Ok(false);
+    /// Whether the game is over after a piece was placed at `last_pos`, either from a line
+    /// through it or from the board filling up
+    pub fn is_terminal_state(&self, last_pos: &[u8]) -> Result<bool, Error> {
+        Ok(self.game_result(last_pos)? != GameResult::Ongoing)
+    }
+
+    /// Remove every piece from the board
+    pub fn reset(&mut self) {
+        self.data.fill(0);
+        self.hash = 0;
+        self.column_heights.clear();
+    }
+
+    /// A copy of the pieces on the board and the state kept alongside them, to roll back to with
+    /// [`restore_snapshot`](Self::restore_snapshot) after any number of moves
+    pub fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot {
+            data: self.data.clone(),
+            hash: self.hash,
+            column_heights: self.column_heights.clone(),
         }
+    }
 
-        // 2 steps as the length of the board is 3 in any dimension (we already got the player from
-        // the starting position
-        for _ in 0..2 {
-            // travel along the direction vector
-            for i in 0..pos.len() {
-                // add each component, limiting to the indexable area (3 in each dimension)
-                pos[i] = (pos[i] as i8 + dir[i]).rem_euclid(3).unsigned_abs();
-            }
+    /// Put the board back as it was when the snapshot was taken. The snapshot must be of this
+    /// board, or at least one of the same shape.
+    pub fn restore_snapshot(&mut self, snapshot: BoardSnapshot) {
+        assert_eq!(snapshot.data.len(), self.data.len(), "Snapshot must be of a board the same size");
 
-            // check if the position is the player
-            if self.get(&pos)? != player {
-                return Ok(false);
-            }
+        self.data.copy_from_slice(&snapshot.data);
+        self.hash = snapshot.hash;
+        self.column_heights = snapshot.column_heights;
+    }
+
+    /// The cells that differ between this board and `other`, which must be the same shape, going
+    /// from this board's values to `other`'s
+    pub fn diff(&self, other: &Self) -> Result<BoardDiff, Error> {
+        if other.sizes.len() != self.sizes.len() {
+            return Err(IndexError::OutOfDimension.into());
+        }
+        if other.sizes != self.sizes {
+            return Err(IndexError::OutOfBounds.into());
         }
 
-        return Ok(true);
+        let changes = self.data.iter()
+            .zip(&other.data)
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(index, (old, new))| (self.position_of(index), *old, *new))
+            .collect();
+
+        Ok(BoardDiff { changes })
     }
-}
 
-impl Drop for Board<'_> {
-    fn drop(&mut self) {
-        let layout = Self::get_layout(self.dimension);
+    /// Write each change in a diff to the board, in order. Every position is checked before
+    /// anything is written, so on an error the board is left as it was.
+    pub fn apply_diff(&mut self, diff: &BoardDiff) -> Result<(), Error> {
+        for (pos, _, _) in &diff.changes {
+            self.index_of(pos)?;
+        }
 
-        unsafe {
-            alloc::dealloc(self.data.as_mut_ptr(), layout);
+        for (pos, _, new) in &diff.changes {
+            *self.get_mut(pos)? = *new;
         }
+
+        self.recompute_zobrist_hash();
+        self.recompute_column_heights();
+        Ok(())
     }
-}
 
+    /// Whether every cell on the board has a piece in it
+    pub fn is_full(&self) -> bool {
+        self.count_empty_cells() == 0
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// The number of pieces a player has on the board
+    pub fn count_pieces_for_player(&self, player: u8) -> usize {
+        self.data.iter().filter(|val| **val == player).count()
+    }
 
-    #[test]
-    fn create_board() {
-        let board = Board::new(3);
-        let expected = [0_u8; 3_usize.pow(3)];
-        let actual = &board.data;
-        
-        // board data with expected data
-        assert!(expected.iter()
-            .zip(actual.iter())
-            .all(|(a, b)| {a == b}) 
-        );
+    /// The number of cells on the board without a piece in them
+    pub fn count_empty_cells(&self) -> usize {
+        self.count_pieces_for_player(0)
     }
 
-    #[test]
-    fn get() {
-        let board = Board::new(4);
-        let expected = 4;
+    /// The number of pieces on the board, counting every player's
+    pub fn num_pieces_total(&self) -> usize {
+        self.data.len() - self.count_empty_cells()
+    }
 
-        // 0 1 2 |  9 10 11 | 18 19 20 \
-        // 3 4 5 | 12 13 14 | 21 22 23 |
-        // 6 7 8 | 15 16 17 | 24 25 26 / 1st 3d slice of 4d tic tac toe, first item in 2nd slice will be index 27
-        board.data[27] = expected; // directly set value
-        let acutal = board.get(&[0,0,0,1]).unwrap(); // get previously set position
+    /// The number of turns played so far, counting from 0 before the first move. The same as
+    /// [`Board::num_pieces_total`], as every turn places one piece.
+    pub fn turn_number(&self) -> usize {
+        self.num_pieces_total()
+    }
 
-        assert_eq!(acutal, expected);
+    /// Whose turn it is if players 1 to `num_players` have taken turns in order from an empty
+    /// board, e.g. player 1 on even turns and player 2 on odd ones in a two-player game. Panics
+    /// if `num_players` is 0.
+    pub fn current_player_guess(&self, num_players: u8) -> u8 {
+        (self.turn_number() % usize::from(num_players)) as u8 + 1
     }
 
-    #[test]
-    fn get_mut() {
-        let mut board = Board::new(6);
-        let expected = 7;
-        let pos = [0,0,0,0,3,0];
+    /// Get the position directly "below" this along the gravity axis (i.e. the position that
+    /// supports the current position), if it needs one
+    fn supporting_position(&self, position: &[u8]) -> Option<Vec<u8>> {
+        let axis = self.gravity_axis?;
 
-        *board.get_mut(&pos).unwrap() = expected;
+        // pieces at the bottom of the axis rest on the edge of the board
+        let below = position.get(axis)?.checked_sub(1)?;
 
-        let actual = board.get(&pos).unwrap();
+        let mut supporting_pos = Vec::from(position);
+        supporting_pos[axis] = below;
 
-        assert_eq!(actual, expected);
+        Some(supporting_pos)
     }
 
-    #[test]
-    fn valid_placement() {
-        let mut board = Board::new(3);
-        let expected = [
-            0,0,0,1,0,0,0,0,0,
-            0,0,0,1,0,0,0,0,0,
-        ];
+    /// Where a piece dropped down the gravity axis would land. `partial_pos` has every coordinate
+    /// except the one along the gravity axis. Errors if the column is full, or with
+    /// `OutOfDimension` if the board has no gravity or the partial position is the wrong length.
+    pub fn drop_position(&self, partial_pos: &[u8]) -> Result<Vec<u8>, Error> {
+        let axis = self.gravity_axis.ok_or(IndexError::OutOfDimension)?;
+        if partial_pos.len() + 1 != self.sizes.len() {
+            return Err(IndexError::OutOfDimension.into());
+        }
 
-        board.place_piece(1, &[0,1,0]).unwrap();
-        board.place_piece(1, &[0,1,1]).unwrap();
+        let mut pos = Vec::from(partial_pos);
+        pos.insert(axis, 0);
+        self.index_of(&pos)?;
 
-        // compare board data with expected data
-        assert!(expected.iter()
-            .zip(board.data.iter())
-            .all(|(a, b)| {a == b}) 
-        );
+        // the cell on top of the stack is the lowest empty one, as every cell under it is taken
+        let height = self.column_heights.get(partial_pos).copied().unwrap_or(0);
+        if height < self.sizes[axis] {
+            pos[axis] = height;
+            return Ok(pos);
+        }
+
+        Err(PlaceError::Unsupported.into())
     }
 
-    #[test]
-    fn unsupported_placement() {
-        let mut board = Board::new(3);
-        let expected = Error::PlaceError(PlaceError::Unsupported);
+    /// Whether the column at `partial_pos` is stacked all the way to the top of the gravity axis,
+    /// looked up from the column heights rather than the cells. `partial_pos` is as for
+    /// [`Board::drop_position`], and errors the same way for a board without gravity.
+    pub fn gravity_column_is_full(&self, partial_pos: &[u8]) -> Result<bool, IndexError> {
+        let axis = self.gravity_axis.ok_or(IndexError::OutOfDimension)?;
+        if partial_pos.len() + 1 != self.sizes.len() {
+            return Err(IndexError::OutOfDimension);
+        }
 
-        let actual = board.place_piece(1, &[0,1,1]).unwrap_err();
+        let mut pos = Vec::from(partial_pos);
+        pos.insert(axis, 0);
+        self.index_of(&pos)?;
 
-        assert!(matches!(actual, expected))
+        Ok(self.column_heights.get(partial_pos).is_some_and(|height| *height == self.sizes[axis]))
     }
 
-    #[test]
-    fn occupied_placement() {
-        let mut board = Board::new(3);
-        let expected = Error::PlaceError(PlaceError::Occupied);
+    /// Remove the piece at a position without checking that it can be removed, i.e. that it isn't
+    /// supporting another piece. Returns the value that was there.
+    pub fn undo_piece_unchecked(&mut self, pos: &[u8]) -> Result<u8, IndexError> {
+        let val = self.get_mut(pos)?;
+        let removed = std::mem::take(val);
+        self.update_zobrist_hash(pos, removed);
 
-        board.place_piece(1, &[0,1,0]).unwrap();
-        let actual = board.place_piece(1, &[0,1,0]).unwrap_err();
-
-        assert!(matches!(actual, expected))
-    }
-    
-    #[test]
-    fn win_dir_straight() {
-        let mut board = Board::new(3);
-        let expected = true;
-        
-        board.place_piece(1, &[0,0,0]).unwrap();
-        board.place_piece(1, &[0,1,0]).unwrap();
-        board.place_piece(1, &[0,2,0]).unwrap();
+        if let Some(axis) = self.gravity_axis {
+            let column = Self::column_of(pos, axis);
+            match pos[axis] {
+                0 => _ = self.column_heights.remove(&column),
+                below => if let Some(height) = self.column_heights.get_mut(&column) {
+                    *height = (*height).min(below);
+                },
+            }
+        }
 
-        let actual = board.check_win_dir(&[0,0,0], &[0,1,0]).unwrap();
+        // only what placing and undoing pieces keep true, pieces can be placed in any order
+        debug_assert!(!self.has_structural_problem(), "invalid board state:\n{}", self.structural_problems().join("\n"));
 
-        assert_eq!(actual, expected);
+        Ok(removed)
     }
-    
-    #[test]
-    fn win_dir_diag() {
-        let mut board = Board::new(3);
-        let expected = true;
-        
-        board.place_piece(1, &[0,0,0]).unwrap();
-        board.place_piece(1, &[1,1,0]).unwrap();
-        board.place_piece(1, &[2,2,0]).unwrap();
 
-        let actual = board.check_win_dir(&[0,0,0], &[1,1,0]).unwrap();
+    /// The zobrist key of a player's piece at an index into `data`. The keys are a fixed
+    /// pseudo-random function of the index and player (splitmix64) rather than a table generated
+    /// per board, so boards of the same shape hash the same pieces the same way.
+    fn zobrist_key(index: usize, player: u8) -> u64 {
+        let mut z = ((index as u64) << 8 | u64::from(player)).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
 
-        assert_eq!(actual, expected);
+    /// The zobrist hash of the board, the XOR of the keys of every piece on it. Pieces written
+    /// straight into `data` or through [`Board::get_mut`] aren't included.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.hash
     }
 
-    #[test]
-    fn win_dir_loop() {
-        let mut board = Board::new(3);
-        let expected = true;
-        
-        board.place_piece(1, &[0,0,0]).unwrap();
-        board.place_piece(1, &[1,1,0]).unwrap();
-        board.place_piece(1, &[2,2,0]).unwrap();
+    /// Whether `current_hash` has come up at least `threshold` times in `history`, a list of
+    /// zobrist hashes of earlier positions, making the game a draw by repetition
+    pub fn check_draw_by_repetition(history: &[u64], current_hash: u64, threshold: usize) -> bool {
+        history.iter().filter(|hash| **hash == current_hash).count() >= threshold
+    }
 
-        let actual = board.check_win_dir(&[2,2,0], &[1,1,0]).unwrap();
+    /// XOR a player's piece at a position into the hash. The same call adds the piece when it is
+    /// placed and takes it back out when it is removed.
+    pub fn update_zobrist_hash(&mut self, pos: &[u8], player: u8) {
+        if player == 0 {
+            return;
+        }
 
-        assert_eq!(actual, expected);
+        if let Ok(index) = self.index_of(pos) {
+            self.hash ^= Self::zobrist_key(index, player);
+        }
     }
 
-    #[test]
-    fn win_dir_no_win() {
-        let mut board = Board::new(3);
-        let expected = false;
-        
-        board.place_piece(1, &[0,0,0]).unwrap();
-
-        let actual = board.check_win_dir(&[0,0,0], &[1,1,0]).unwrap();
+    /// Write the board out as bytes: the dimension as a little endian `u16`, the length of each
+    /// side, the win length, the mode (0 standard, 1 misère, 2 wild), the gravity axis plus one (0
+    /// for no gravity) as a little endian `u16`, and then every cell as it is stored in `data`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mode = match self.mode {
+            GameMode::Standard => 0,
+            GameMode::Misere => 1,
+            GameMode::Wild => 2,
+        };
+        // the gravity axis is one of at most u16::MAX axes, so it fits with the offset
+        let gravity = self.gravity_axis.map_or(0, |axis| axis as u16 + 1);
 
-        assert_eq!(actual, expected);
+        let mut bytes = Vec::with_capacity(self.sizes.len() + 6 + self.data.len());
+        bytes.extend_from_slice(&self.dimension.to_le_bytes());
+        bytes.extend_from_slice(&self.sizes);
+        bytes.extend_from_slice(&[self.win_length, mode]);
+        bytes.extend_from_slice(&gravity.to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        bytes
     }
 
-    #[test]
-    fn win_no_win() {
-        let mut board = Board::new(2);
-        let expected = false;
+    /// Read a board back from the bytes written by [`Board::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let (dimension, rest) = bytes.split_first_chunk::<2>().ok_or(DeserializeError::Truncated)?;
+        let dimension = u16::from_le_bytes(*dimension);
+        let (sizes, rest) = rest.split_at_checked(dimension.into()).ok_or(DeserializeError::Truncated)?;
+        let (header, data) = rest.split_first_chunk::<4>().ok_or(DeserializeError::Truncated)?;
+        let [win_length, mode, gravity @ ..] = *header;
+        let gravity = u16::from_le_bytes(gravity);
 
-        let actual = board.place_piece(1, &[0,2]).unwrap();
+        let mode = match mode {
+            0 => GameMode::Standard,
+            1 => GameMode::Misere,
+            2 => GameMode::Wild,
+            _ => return Err(DeserializeError::InvalidHeader),
+        };
+        let gravity_axis = gravity.checked_sub(1).map(usize::from);
+
+        if sizes.is_empty()
+            || sizes.contains(&0)
+            || win_length == 0
+            || win_length > sizes.iter().copied().max().unwrap_or(1)
+            || gravity_axis.is_some_and(|axis| axis >= sizes.len())
+        {
+            return Err(DeserializeError::InvalidHeader);
+        }
+        if Self::get_data_length(sizes) != Some(data.len()) {
+            return Err(DeserializeError::WrongDataLength);
+        }
+
+        let mut board = Self::new_rect(sizes);
+        board.win_length = win_length;
+        board.mode = mode;
+        board.data.copy_from_slice(data);
+        board.recompute_zobrist_hash();
+        board.set_gravity_axis(gravity_axis);
+
+        Ok(board)
+    }
+
+    /// Every piece on the board as `player@(coords)`, separated by spaces, e.g.
+    /// `1@(0,0,0) 2@(1,1,1)`. Only the pieces are written, not the shape of the board or its rules.
+    pub fn compact_notation(&self) -> String {
+        self.occupied_positions()
+            .map(|pos| {
+                let coords = pos.iter().map(u8::to_string).collect::<Vec<_>>().join(",");
+                format!("{}@({coords})", self.cell(&pos).unwrap_or(0))
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// A hypercube board with the pieces from [`Board::compact_notation`] put straight into its
+    /// cells. Errors if a position isn't on the board, or with `InvalidFormat` for a cell that is
+    /// listed twice, a player of 0, or a board without any cells.
+    pub fn from_compact_notation(s: &str, dimension: u16, size: u8) -> Result<Self, ParseError> {
+        if dimension == 0 || size == 0 {
+            return Err(ParseError::InvalidFormat);
+        }
+
+        let mut board = Self::new_with_size(dimension, size);
+        for piece in s.split_whitespace() {
+            let (player, coords) = piece.split_once('@').ok_or(ParseError::InvalidFormat)?;
+            let coords = coords.strip_prefix('(')
+                .and_then(|coords| coords.strip_suffix(')'))
+                .ok_or(ParseError::InvalidFormat)?;
+
+            let player: u8 = player.parse()?;
+            let pos = Position::from_str(coords, dimension, size)?;
+
+            let cell = board.get_mut(&pos)?;
+            if player == 0 || *cell != 0 {
+                return Err(ParseError::InvalidFormat);
+            }
+            *cell = player;
+        }
+        board.recompute_zobrist_hash();
+
+        Ok(board)
+    }
+
+    /// Hash every piece on the board from scratch, for when pieces were put in place without
+    /// going through `place_piece`
+    fn recompute_zobrist_hash(&mut self) {
+        self.hash = self.data.iter()
+            .enumerate()
+            .filter(|(_, val)| **val != 0)
+            .fold(0, |hash, (index, val)| hash ^ Self::zobrist_key(index, *val));
+    }
+
+    /// A copy of the board with the piece at each position moved to `map(position)`, which must
+    /// land on the board and must not map two positions to the same one
+    fn remapped(&self, map: impl Fn(&[u8]) -> Vec<u8>) -> Self {
+        let mut board = self.clone();
+        board.data.fill(0);
+
+        for pos in self.occupied_positions() {
+            *board.get_mut(&map(&pos)).expect("positions are mapped onto the board") = self.cell(&pos).unwrap();
+        }
+
+        board.recompute_zobrist_hash();
+        board.recompute_column_heights();
+        board
+    }
+
+    /// A copy of the board flipped along an axis, so the piece at coordinate `c` on the axis moves
+    /// to `size - 1 - c`
+    pub fn mirror(&self, axis: usize) -> Self {
+        assert!(axis < self.sizes.len(), "Mirror axis must be one of the board's dimensions");
+
+        self.remapped(|pos| {
+            let mut mirrored = Vec::from(pos);
+            mirrored[axis] = self.sizes[axis] - 1 - pos[axis];
+            mirrored
+        })
+    }
+
+    /// A copy of the board turned a quarter turn in the plane of two axes, so the piece at `c`
+    /// moves to where `c[axis_a]` is `size - 1 - c[axis_b]` and `c[axis_b]` is `c[axis_a]`. The
+    /// two axes must be different and the same length.
+    pub fn rotate(&self, axis_a: usize, axis_b: usize) -> Self {
+        assert!(axis_a < self.sizes.len() && axis_b < self.sizes.len(), "Rotation axes must be among the board's dimensions");
+        assert!(axis_a != axis_b, "Rotation needs two different axes");
+        assert!(self.sizes[axis_a] == self.sizes[axis_b], "Rotation axes must be the same length");
+
+        self.remapped(|pos| {
+            let mut rotated = Vec::from(pos);
+            rotated[axis_a] = self.sizes[axis_b] - 1 - pos[axis_b];
+            rotated[axis_b] = pos[axis_a];
+            rotated
+        })
+    }
+
+    /// A board with only the `kept_axes`, in the order given, where a cell is 1 if any cell of
+    /// this board projecting onto it is occupied and 0 if they're all empty. Which player is where
+    /// is lost, leaving the parts of the board in use.
+    pub fn project_onto_subspace(&self, kept_axes: &[usize]) -> Self {
+        assert!(kept_axes.iter().all(|axis| *axis < self.sizes.len()), "Kept axes must be among the board's dimensions");
+        assert!(
+            kept_axes.iter().enumerate().all(|(i, axis)| !kept_axes[..i].contains(axis)),
+            "Kept axes must be different",
+        );
+
+        let sizes: Vec<u8> = kept_axes.iter().map(|axis| self.sizes[*axis]).collect();
+        let mut board = Self::new_rect(&sizes);
+
+        for pos in self.occupied_positions() {
+            let projected: Vec<u8> = kept_axes.iter().map(|axis| pos[*axis]).collect();
+            *board.get_mut(&projected).expect("projected positions are on the board") = 1;
+        }
+
+        board.recompute_zobrist_hash();
+        board
+    }
+
+    /// Copy `sub` into the slice of this board through `kept_axes`, the reverse of
+    /// [`project_onto_subspace`](Self::project_onto_subspace). Axis `i` of `sub` runs along
+    /// `kept_axes[i]` and must be the same length, and the other axes, in order, are fixed at
+    /// `fixed_coords`. Every cell of the slice is overwritten, empty ones included.
+    pub fn embed_sub_board(&mut self, sub: &Board, kept_axes: &[usize], fixed_coords: &[u8]) -> Result<(), IndexError> {
+        let dimension = self.sizes.len();
+        if kept_axes.len() != sub.sizes.len() || kept_axes.len() + fixed_coords.len() != dimension {
+            return Err(IndexError::OutOfDimension);
+        }
+        if kept_axes.iter().enumerate().any(|(i, axis)| *axis >= dimension || kept_axes[..i].contains(axis)) {
+            return Err(IndexError::OutOfDimension);
+        }
+
+        let mut pos = vec![0; dimension];
+        let fixed_axes = (0..dimension).filter(|axis| !kept_axes.contains(axis));
+        for (axis, val) in fixed_axes.zip(fixed_coords) {
+            if *val >= self.sizes[axis] {
+                return Err(IndexError::OutOfBounds);
+            }
+            pos[axis] = *val;
+        }
+        if kept_axes.iter().zip(&sub.sizes).any(|(axis, size)| self.sizes[*axis] != *size) {
+            return Err(IndexError::OutOfBounds);
+        }
+
+        for sub_pos in sub.positions() {
+            for (axis, val) in kept_axes.iter().zip(&sub_pos) {
+                pos[*axis] = *val;
+            }
+            *self.get_mut(&pos)? = sub.cell(&sub_pos)?;
+        }
+
+        self.recompute_zobrist_hash();
+        self.recompute_column_heights();
+        Ok(())
+    }
+
+    /// A copy of the board with its axes reordered, so coordinate `i` of the result is coordinate
+    /// `perm[i]` of this board. `perm` must be a permutation of `0..dimension`. The sizes and the
+    /// gravity axis move along with the axes.
+    pub fn permute_axes(&self, perm: &[usize]) -> Result<Self, IndexError> {
+        let dimension = self.sizes.len();
+        if perm.len() != dimension || (0..dimension).any(|axis| !perm.contains(&axis)) {
+            return Err(IndexError::OutOfDimension);
+        }
+
+        let sizes: Vec<u8> = perm.iter().map(|axis| self.sizes[*axis]).collect();
+        let mut board = Self::new_rect(&sizes);
+        board.win_length = self.win_length;
+        board.mode = self.mode;
+        board.gravity_axis = self.gravity_axis.and_then(|axis| perm.iter().position(|from| *from == axis));
+
+        for pos in self.occupied_positions() {
+            let permuted: Vec<u8> = perm.iter().map(|from| pos[*from]).collect();
+            *board.get_mut(&permuted)? = self.cell(&pos)?;
+        }
+
+        board.recompute_zobrist_hash();
+        board.recompute_column_heights();
+        Ok(board)
+    }
+
+    /// Every symmetry of the board as an axis permutation and the axes to flip after it: the
+    /// position `p` maps to the one with `p[perm[i]]` at axis `i`, flipped if `flips[i]`. Only
+    /// axes of the same length are swapped, and the gravity axis stays where it is the right way
+    /// up. On a hypercube without gravity that's the full `2^n * n!` hyperoctahedral group.
+    fn symmetries(&self) -> Vec<(Vec<usize>, Vec<bool>)> {
+        let dimension = self.sizes.len();
+
+        // build up the permutations an axis at a time
+        let mut perms: Vec<Vec<usize>> = vec![Vec::new()];
+        for axis in 0..dimension {
+            perms = perms.into_iter()
+                .flat_map(|perm| {
+                    (0..dimension)
+                        .filter(|from| !perm.contains(from) && self.sizes[*from] == self.sizes[axis])
+                        .filter(|from| self.gravity_axis.is_none_or(|gravity| (axis == gravity) == (*from == gravity)))
+                        .map(|from| {
+                            let mut perm = perm.clone();
+                            perm.push(from);
+                            perm
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        }
+
+        let flips = (0..1_usize << dimension)
+            .map(|bits| (0..dimension).map(|axis| bits >> axis & 1 == 1).collect::<Vec<_>>())
+            .filter(|flips| self.gravity_axis.is_none_or(|gravity| !flips[gravity]))
+            .collect::<Vec<_>>();
+
+        perms.iter()
+            .flat_map(|perm| flips.iter().map(|flips| (perm.clone(), flips.clone())))
+            .collect()
+    }
+
+    /// A copy of the board with a symmetry from [`Board::symmetries`] applied
+    fn apply_symmetry(&self, perm: &[usize], flips: &[bool]) -> Self {
+        self.remapped(|pos| {
+            perm.iter()
+                .zip(flips)
+                .zip(&self.sizes)
+                .map(|((from, flip), size)| if *flip { size - 1 - pos[*from] } else { pos[*from] })
+                .collect()
+        })
+    }
+
+    /// The board's representative among every board it can be turned into by rotating and
+    /// mirroring: the one with the smallest zobrist hash. Boards that are rotations or mirrors of
+    /// each other have the same canonical form, so it can key a transposition table. Gravity is
+    /// respected, the gravity axis is never moved or flipped.
+    pub fn to_canonical_form(&self) -> Self {
+        self.symmetries()
+            .iter()
+            .map(|(perm, flips)| self.apply_symmetry(perm, flips))
+            .min_by_key(|board| board.zobrist_hash())
+            .expect("the identity is always a symmetry")
+    }
+
+    /// How many different boards the board can be turned into by rotating and mirroring, itself
+    /// included, told apart by zobrist hash. An empty board only has itself, and a lone piece in a
+    /// corner of a hypercube can be moved to any of its `2^n` corners. Gravity is respected like in
+    /// [`Board::to_canonical_form`].
+    pub fn symmetry_class_size(&self) -> usize {
+        self.symmetries()
+            .iter()
+            .map(|(perm, flips)| self.apply_symmetry(perm, flips).zobrist_hash())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// The position of the cell at an index into `data`, the reverse of the indexing in `get`
+    fn position_of(&self, mut index: usize) -> Vec<u8> {
+        self.sizes.iter()
+            .map(|size| {
+                let val = index % usize::from(*size);
+                index /= usize::from(*size);
+                val as u8
+            })
+            .collect()
+    }
+
+    /// Check that the board could have been reached by legal play: the data is the right length,
+    /// every piece is supported under gravity, and unless the game is Wild, the pieces are what
+    /// players taking turns from player 1 would leave. That means no player's piece without a
+    /// piece from every player numbered before them, and no player more than a piece ahead of
+    /// another. Returns false on the first problem found, use [`Board::run_self_check`] to find
+    /// out what is wrong.
+    pub fn is_valid_board_state(&self) -> bool {
+        !self.has_structural_problem() && self.turn_order_problems().is_empty()
+    }
+
+    /// Whether the data is the wrong length or a piece is unsupported, which placing and undoing
+    /// pieces never cause
+    fn has_structural_problem(&self) -> bool {
+        Self::get_data_length(&self.sizes) != Some(self.data.len()) || self.has_unsupported_piece()
+    }
+
+    /// Whether any piece is floating above the bottom of the gravity axis without a piece
+    /// directly below it, which placing pieces never allows. Always false without gravity.
+    pub fn has_unsupported_piece(&self) -> bool {
+        self.gravity_axis.is_some() && self.occupied_positions().any(|pos| !self.is_supported(&pos))
+    }
+
+    /// Whether an occupied position would have been allowed by gravity
+    fn is_supported(&self, pos: &[u8]) -> bool {
+        match self.supporting_position(pos) {
+            Some(supporting_pos) => self.cell(&supporting_pos).is_ok_and(|val| val != 0),
+            None => true,
+        }
+    }
+
+    /// Describe every problem with the board state, see [`Board::is_valid_board_state`]. Empty if
+    /// the board is valid.
+    pub fn run_self_check(&self) -> Vec<String> {
+        let mut problems = self.structural_problems();
+        if problems.is_empty() {
+            problems.extend(self.turn_order_problems());
+        }
+
+        problems
+    }
+
+    /// Describe every problem [`Board::has_structural_problem`] finds
+    fn structural_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let expected_length = Self::get_data_length(&self.sizes).unwrap_or(usize::MAX);
+        if self.data.len() != expected_length {
+            problems.push(format!("data has length {} but a board of dimension {} needs {}",
+                self.data.len(), self.dimension, expected_length));
+            // positions can't be trusted to line up with the data
+            return problems;
+        }
+
+        for (i, val) in self.data.iter().enumerate() {
+            let pos = self.position_of(i);
+
+            if *val != 0 && !self.is_supported(&pos) {
+                problems.push(format!("piece of player {val} at {pos:?} is not supported"));
+            }
+        }
+
+        problems
+    }
+
+    /// Describe how the number of pieces each player has couldn't come from players taking turns,
+    /// see [`Board::is_valid_board_state`]. Anything goes in a Wild game, where any player's piece
+    /// can be placed on any turn.
+    fn turn_order_problems(&self) -> Vec<String> {
+        if self.mode == GameMode::Wild {
+            return Vec::new();
+        }
+
+        let last_player = self.data.iter().copied().max().unwrap_or(0);
+        let mut counts = vec![0_usize; usize::from(last_player) + 1];
+        for val in &self.data {
+            counts[usize::from(*val)] += 1;
+        }
+
+        let mut problems = Vec::new();
+        let first = counts.get(1).copied().unwrap_or(0);
+        for player in 1..=last_player {
+            let count = counts[usize::from(player)];
+            let previous = counts[usize::from(player) - 1];
+
+            if count == 0 {
+                problems.push(format!("piece of player {last_player} on the board but none of player {player}"));
+            } else if count > first || count + 1 < first || (player > 1 && count > previous) {
+                problems.push(format!("player {player} has {count} pieces, out of turn with player 1's {first}"));
+            }
+        }
+
+        problems
+    }
+
+    /// Check everything the board keeps track of agrees with its cells: the data is the right
+    /// length, every piece is supported under gravity, and the column heights and zobrist hash
+    /// match what they'd be worked out from scratch. Errors with the first problem found.
+    ///
+    /// Writing to cells directly, e.g. through [`Board::get_mut`], skips the bookkeeping, so only
+    /// boards changed through placing and undoing pieces are expected to pass.
+    pub fn validate_invariants(&self) -> Result<(), String> {
+        if let Some(problem) = self.structural_problems().into_iter().next() {
+            return Err(problem);
+        }
+
+        let mut expected = self.clone();
+        expected.recompute_column_heights();
+        expected.recompute_zobrist_hash();
+
+        if self.column_heights != expected.column_heights {
+            return Err(format!("column heights are {:?} but the pieces stack to {:?}",
+                self.column_heights, expected.column_heights));
+        }
+        if self.hash != expected.hash {
+            return Err(format!("zobrist hash is {:#x} but the pieces hash to {:#x}", self.hash, expected.hash));
+        }
+
+        Ok(())
+    }
+
+    /// Panic with the output of [`Board::run_self_check`] if the board state is invalid
+    pub fn assert_valid(&self) {
+        if !self.is_valid_board_state() {
+            panic!("invalid board state:\n{}", self.run_self_check().join("\n"));
+        }
+    }
+
+    /// Place a piece under misère rules whatever the board's mode, where completing a line loses
+    /// the game. A completed line is reported as a [`GameResult::Loss`] for `player`, the same
+    /// as placing on a board in [`GameMode::Misere`].
+    pub fn play_misere(&mut self, player: u8, pos: &[u8]) -> Result<GameResult, Error> {
+        match self.place_piece_number(player, pos)? {
+            GameResult::Win(winner) => Ok(GameResult::Loss(winner)),
+            state => Ok(state),
+        }
+    }
+
+    /// Place a piece of any player's type, as in Wild tic-tac-toe. Completing a line of any piece
+    /// type is a win for `actor`, the player making the move, not for `piece_player`.
+    pub fn place_any_piece(&mut self, actor: u8, piece_player: u8, pos: &[u8]) -> Result<GameResult, Error> {
+        match self.place_piece_number(piece_player, pos)? {
+            GameResult::Win(_) => Ok(GameResult::Win(actor)),
+            state => Ok(state),
+        }
+    }
+
+    /// The player with a winning line anywhere on the board, for when the last move isn't known,
+    /// e.g. after loading a saved board. Checks [`Board::is_win_at`] at every occupied position
+    /// in turn, so if more than one player has a line the one found first wins.
+    pub fn winning_player(&self) -> Option<Player> {
+        self.occupied_positions().find_map(|pos| match self.is_win_at(&pos) {
+            Ok(WinResult::Win { player, .. }) => Player::new(player),
+            _ => None,
+        })
+    }
+
+    /// Check to see if there is a win at the given position. Intended to be used directly after
+    /// placing a piece to detect a winning move. 
+    pub fn is_win_at(&self, pos: &[u8]) -> Result<WinResult, Error> {
+        if pos.len() != usize::from(self.dimension) {
+            return Err(IndexError::OutOfDimension.into());
+        }
+
+        if let Some(lines) = &self.win_lines {
+            let player = self.cell(pos)?;
+            if player == 0 {
+                return Ok(WinResult::NoWin);
+            }
+
+            let line = lines.iter().find(|line| {
+                line.iter().any(|other| other.as_slice() == pos)
+                    && line.iter().all(|other| self.cell(other) == Ok(player))
+            });
+
+            return Ok(match line {
+                Some(line) => WinResult::Win { player, line: line.clone() },
+                None => WinResult::NoWin,
+            });
+        }
+
+        // with only a handful of directions, handing them out to threads costs more than it saves
+        #[cfg(feature = "rayon")]
+        let line = if pos.len() >= 4 {
+            self.find_win_line_parallel(pos)?
+        } else {
+            self.find_win_line(pos)?
+        };
+        #[cfg(not(feature = "rayon"))]
+        let line = self.find_win_line(pos)?;
+
+        match line {
+            Some(line) => Ok(WinResult::Win { player: self.cell(pos)?, line }),
+            None => Ok(WinResult::NoWin),
+        }
+    }
+
+    /// The first winning line through a position, going through the directions in order
+    fn find_win_line(&self, pos: &[u8]) -> Result<Option<Vec<Vec<u8>>>, Error> {
+        // the key will be to just check_win_dir each directional vector from the position
+        for dir in Self::directions(pos.len()).iter() {
+            if let Some(line) = self.check_win_dir(pos, dir)? {
+                return Ok(Some(line));
+            }
+        }
+
+        // no win found
+        Ok(None)
+    }
+
+    /// [`find_win_line`](Self::find_win_line) with the directions checked in parallel. The line
+    /// found is still the one in the first direction with a win, so the result is the same.
+    #[cfg(feature = "rayon")]
+    fn find_win_line_parallel(&self, pos: &[u8]) -> Result<Option<Vec<Vec<u8>>>, Error> {
+        use rayon::prelude::*;
+
+        Self::directions(pos.len())
+            .par_iter()
+            .find_map_first(|dir| self.check_win_dir(pos, dir).transpose())
+            .transpose()
+    }
+
+    /// Every line on the board that wins when filled by one player, grouped by direction
+    pub fn compute_win_lines(&self) -> Vec<Vec<Vec<u8>>> {
+        self.all_lines().map(|line| line.positions).collect()
+    }
+
+    /// Iterate over every line that wins when filled by one player, i.e. every run of in-bounds
+    /// positions that is the number needed to win along its direction long, without wrapping
+    pub fn all_lines(&self) -> LineIter<'_> {
+        LineIter {
+            board: self,
+            directions: Self::directions(self.dimension.into()).to_vec(),
+            dir: 0,
+            index: 0,
+        }
+    }
+
+    /// Every line from [`Board::all_lines`] that `pos` is on, e.g. the row, column and both
+    /// diagonals through the center of a classic board
+    pub fn lines_through_position(&self, pos: &[u8]) -> Vec<Line> {
+        self.all_lines()
+            .filter(|line| line.positions.iter().any(|line_pos| line_pos == pos))
+            .collect()
+    }
+
+    /// Call `f` with the positions of every line that `pos` is on, like
+    /// [`Board::lines_through_position`] but walking out from `pos` instead of collecting every
+    /// line on the board. Stops as soon as `f` breaks, and never calls it if `pos` is off the
+    /// board.
+    pub fn for_each_line_through(&self, pos: &[u8], mut f: impl FnMut(&[Vec<u8>]) -> ControlFlow<()>) {
+        if self.index_of(pos).is_err() {
+            return;
+        }
+
+        for dir in Self::directions(pos.len()).iter() {
+            let run = self.line_through(pos, dir);
+
+            for line in run.windows(self.line_length(dir)) {
+                if line.iter().any(|other| other.as_slice() == pos) && f(line).is_break() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// How favorable a line is for a player: 10 if they have filled it, 1 for each of their
+    /// pieces if nobody else has a piece on it, -1 for each piece of an opponent who has it to
+    /// themselves, and 0 if it is empty or more than one player has a piece on it
+    pub fn line_score(&self, line: &Line, player: u8) -> i32 {
+        let mut owner = None;
+        let mut pieces = 0;
+
+        for pos in &line.positions {
+            match self.cell(pos) {
+                Ok(0) | Err(_) => continue,
+                Ok(val) if owner.is_none_or(|owner| owner == val) => owner = Some(val),
+                Ok(_) => return 0,
+            }
+            pieces += 1;
+        }
+
+        match owner {
+            Some(owner) if owner == player && pieces == line.positions.len() => 10,
+            Some(owner) if owner == player => pieces as i32,
+            Some(_) => -(pieces as i32),
+            None => 0,
+        }
+    }
+
+    /// The sum of [`Board::line_score`] over every line on the board
+    pub fn board_score(&self, player: u8) -> i32 {
+        self.all_lines().map(|line| self.line_score(&line, player)).sum()
+    }
+
+    /// A weighted score of the lines `player` has to themselves: each line with `k` of their
+    /// pieces and none of anyone else's adds `weights[k]`, with lines left out of `weights`
+    /// adding nothing. For a hypercube board `weights` wants the side length plus 1 entries, see
+    /// [`Board::default_weights`].
+    pub fn score_player_lines(&self, player: u8, weights: &[i32]) -> i32 {
+        self.all_lines()
+            .filter_map(|line| {
+                let mut pieces = 0;
+                for pos in &line.positions {
+                    match self.cell(pos) {
+                        Ok(0) | Err(_) => {},
+                        Ok(val) if val == player => pieces += 1,
+                        Ok(_) => return None,
+                    }
+                }
+                weights.get(pieces).copied()
+            })
+            .sum()
+    }
+
+    /// Weights for [`Board::score_player_lines`] that grow tenfold with each piece on a line,
+    /// starting from nothing for an empty line, e.g. `[0, 1, 10, 100]` for a side length of 3
+    pub fn default_weights(size: u8) -> Vec<i32> {
+        std::iter::once(0)
+            .chain((0..u32::from(size)).map(|k| 10_i32.saturating_pow(k)))
+            .collect()
+    }
+
+    /// The number of lines on the board filled entirely by `player`
+    pub fn count_lines_won_by(&self, player: u8) -> usize {
+        self.all_lines()
+            .filter(|line| self.is_line_won_by(line, player))
+            .count()
+    }
+
+    /// The number of wins `player` has on the board, another name for
+    /// [`Board::count_lines_won_by`]. Unlike [`Board::threat_count_for_player`] only complete lines count.
+    pub fn num_winning_lines_for_player(&self, player: u8) -> usize {
+        self.count_lines_won_by(player)
+    }
+
+    /// Every line on the board filled entirely by `player`, e.g. to highlight all of them at once
+    /// when one move completes more than one
+    pub fn all_winning_lines_for_player(&self, player: u8) -> Vec<Line> {
+        self.all_lines()
+            .filter(|line| self.is_line_won_by(line, player))
+            .collect()
+    }
+
+    /// The fewest pieces `player` needs to add to complete a line no other player has a piece
+    /// in, 0 if they already have one, or `None` if every line is blocked. Gravity isn't taken
+    /// into account, so the cells may not all be playable yet.
+    pub fn distance_to_win(&self, player: u8) -> Option<u32> {
+        self.all_lines()
+            .filter_map(|line| {
+                let mut missing = 0;
+                for pos in &line.positions {
+                    match self.cell(pos) {
+                        Ok(0) => missing += 1,
+                        Ok(val) if val == player => {},
+                        _ => return None,
+                    }
+                }
+                Some(missing)
+            })
+            .min()
+    }
+
+    fn is_line_won_by(&self, line: &Line, player: u8) -> bool {
+        line.positions.iter().all(|pos| self.cell(pos) == Ok(player))
+    }
+
+    /// The lowest numbered player who has filled a line anywhere on the board, found by scanning
+    /// every line rather than checking each cell with [`Board::is_win_at`]. Under misère rules this
+    /// is the first player to have lost, as the game mode isn't taken into account.
+    pub fn any_winner(&self) -> Option<u8> {
+        let last_player = self.data.iter().copied().max().unwrap_or(0);
+
+        (1..=last_player).find(|player| self.count_lines_won_by(*player) > 0)
+    }
+
+    /// Compute the winning lines once and keep them, so later win checks only have to look
+    /// through the lines instead of walking the board
+    pub fn precompute_wins(&mut self) {
+        self.win_lines = Some(self.compute_win_lines());
+    }
+
+    /// The winning lines, from the cache if they were precomputed
+    fn win_lines(&self) -> std::borrow::Cow<'_, [Vec<Vec<u8>>]> {
+        match &self.win_lines {
+            Some(lines) => lines.into(),
+            None => self.compute_win_lines().into(),
+        }
+    }
+
+    /// Every legal move that would win the game for a player straight away
+    pub fn immediate_winning_moves(&self, player: u8) -> Vec<Vec<u8>> {
+        // each move is tried out on a copy and then taken back
+        let mut board = self.clone();
+
+        self.legal_moves(player)
+            .into_iter()
+            .filter(|pos| {
+                let won = matches!(board.place_piece_number(player, pos), Ok(GameResult::Win(_)));
+                board.undo_piece_unchecked(pos).expect("the move was just placed");
+                won
+            })
+            .collect()
+    }
+
+    /// The first position where a player could complete a line with their next piece, found by
+    /// looking along the lines through the player's pieces instead of trying every legal move
+    pub fn can_win_in_one(&self, player: u8) -> Option<Vec<u8>> {
+        if player == 0 {
+            return None;
+        }
+
+        let directions = Self::directions(self.dimension.into());
+
+        self.positions_of_player(player).find_map(|pos| {
+            directions.iter()
+                .flat_map(|dir| self.lines_through_dir(&pos, dir))
+                .find_map(|line| {
+                    let mut empty = line.iter().filter(|other| self.cell(other) == Ok(0));
+                    let empty_pos = empty.next()?;
+
+                    let wins = empty.next().is_none()
+                        && line.iter().all(|other| other == empty_pos || self.cell(other) == Ok(player))
+                        && self.can_place_at(empty_pos);
+                    wins.then(|| empty_pos.clone())
+                })
+        })
+    }
+
+    /// Every legal move that would leave a player with two or more threats at once, see
+    /// [`Board::threats_for_player`]
+    pub fn fork_positions(&self, player: u8) -> Vec<Vec<u8>> {
+        // each move is tried out on a copy and then taken back
+        let mut board = self.clone();
+
+        self.legal_moves(player)
+            .into_iter()
+            .filter(|pos| {
+                board.place_piece_number(player, pos).expect("legal moves can be placed");
+                let threats = board.threats_for_player(player).len();
+                board.undo_piece_unchecked(pos).expect("the move was just placed");
+
+                threats >= 2
+            })
+            .collect()
+    }
+
+    /// Every position that has to be taken to stop an opponent winning on their next move
+    pub fn blocking_moves(&self, _player: u8, opponent: u8) -> Vec<Vec<u8>> {
+        let mut moves = Vec::new();
+
+        for threat in self.threats_for_player(opponent) {
+            if !moves.contains(&threat.empty_pos) {
+                moves.push(threat.empty_pos);
+            }
+        }
+
+        moves
+    }
+
+    /// Every line where a player has all but one of the pieces and the last cell can be placed in
+    /// now. A position that shows up in more than one threat is a fork.
+    pub fn threats_for_player(&self, player: u8) -> Vec<Threat> {
+        self.win_lines().iter()
+            .filter_map(|line| {
+                self.threat_on_line(line, player).map(|empty_pos| Threat {
+                    player,
+                    empty_pos: empty_pos.clone(),
+                    line: line.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// The number of threats a player has, as in [`Board::threats_for_player`] but without
+    /// building them
+    pub fn threat_count_for_player(&self, player: u8) -> usize {
+        self.win_lines().iter()
+            .filter(|line| self.threat_on_line(line, player).is_some())
+            .count()
+    }
+
+    /// Every empty position that completes two or more of `attacker`'s threats at once, which is
+    /// where another player can block more than one of them with a single piece. In lexicographic
+    /// order.
+    pub fn two_way_threats(&self, attacker: u8) -> Vec<Vec<u8>> {
+        let mut counts: HashMap<Vec<u8>, usize> = HashMap::new();
+        for threat in self.threats_for_player(attacker) {
+            *counts.entry(threat.empty_pos).or_default() += 1;
+        }
+
+        let mut positions: Vec<_> = counts.into_iter()
+            .filter(|(_, count)| *count >= 2)
+            .map(|(pos, _)| pos)
+            .collect();
+        positions.sort_unstable();
+
+        positions
+    }
+
+    /// The one empty cell of a line a player has all the other pieces of, if it can be placed in
+    /// now
+    fn threat_on_line<'l>(&self, line: &'l [Vec<u8>], player: u8) -> Option<&'l Vec<u8>> {
+        let mut empty = line.iter().filter(|pos| self.cell(pos) == Ok(0));
+        let empty_pos = empty.next()?;
+
+        let only_empty = empty.next().is_none();
+        let rest_player = line.iter()
+            .all(|pos| pos == empty_pos || self.cell(pos) == Ok(player));
+
+        (only_empty && rest_player && self.can_place_at(empty_pos)).then_some(empty_pos)
+    }
+
+    /// A single number for how well `player` is doing against `opponent`: 1000 if they have
+    /// completed a line, plus 100 for each of their threats, less 100 for each of the opponent's,
+    /// plus [`Board::score_player_lines`] with [`Board::default_weights`] for the win length
+    pub fn total_eval_score(&self, player: u8, opponent: u8) -> i32 {
+        let won = self.count_lines_won_by(player) > 0;
+        let threats = self.threat_count_for_player(player) as i32;
+        let opponent_threats = self.threat_count_for_player(opponent) as i32;
+        let weights = Self::default_weights(self.win_length());
+
+        1000 * i32::from(won) + 100 * threats - 100 * opponent_threats + self.score_player_lines(player, &weights)
+    }
+
+    /// Every direction a line can run along in the given dimension. A direction and its opposite
+    /// describe the same line, so only the one whose first non-zero component is positive is
+    /// included, leaving (3^n - 1) / 2 directions.
+    pub fn all_directions(dimension: usize) -> Vec<Direction> {
+        let mut directions = Vec::with_capacity((3_usize.pow(dimension.try_into().unwrap()) - 1) / 2);
+
+        // setup a direction vector that we'll use to calculate each neighbor direction
+        let mut dir: Direction = vec![-1; dimension];
+
+        loop {
+            // skip the vector that points nowhere (and any below it, which are the opposites of
+            // those above)
+            if dir.iter().find(|n| **n != 0) == Some(&1) {
+                directions.push(dir.clone());
+            }
+
+            // add one at the last dimension, propagating the addition up dimensions for any
+            // component that is beyond pointing to a neighbor |1| or 0
+            let Some(i) = dir.iter().rposition(|n| *n < 1) else {
+                break;
+            };
+            dir[i] += 1;
+            dir[i+1..].fill(-1);
+        }
+
+        directions
+    }
+
+    /// The same as [`Board::all_directions`], but only calculated once for each dimension
+    #[cfg(feature = "cached-directions")]
+    pub fn all_directions_cached(dimension: usize) -> &'static [Direction] {
+        use std::collections::HashMap;
+        use std::sync::{LazyLock, Mutex};
+
+        // the directions are leaked so they can be handed out for the rest of the program
+        static CACHE: LazyLock<Mutex<HashMap<usize, &'static [Direction]>>> = LazyLock::new(Default::default);
+
+        let mut cache = CACHE.lock().unwrap();
+        cache.entry(dimension)
+            .or_insert_with(|| Self::all_directions(dimension).leak())
+    }
+
+    #[cfg(feature = "cached-directions")]
+    fn directions(dimension: usize) -> &'static [Direction] {
+        Self::all_directions_cached(dimension)
+    }
+
+    #[cfg(not(feature = "cached-directions"))]
+    fn directions(dimension: usize) -> Vec<Direction> {
+        Self::all_directions(dimension)
+    }
+
+    /// Every position one step away from a position in any direction, diagonals included. Steps
+    /// that would leave the board are left out rather than wrapping around. Empty if the position
+    /// isn't on the board.
+    pub fn neighbors(&self, pos: &[u8]) -> Vec<Vec<u8>> {
+        if self.index_of(pos).is_err() {
+            return Vec::new();
+        }
+
+        Self::directions(pos.len()).iter()
+            .flat_map(|dir| [self.step(pos, dir, -1), self.step(pos, dir, 1)])
+            .flatten()
+            .collect()
+    }
+
+    /// The position one step along a direction (or against it, with a `sign` of -1), or `None` if
+    /// that would leave the board
+    fn step(&self, pos: &[u8], dir: &[i8], sign: i8) -> Option<Vec<u8>> {
+        pos.iter()
+            .zip(dir)
+            .zip(&self.sizes)
+            .map(|((val, d), size)| val.checked_add_signed(d * sign).filter(|val| val < size))
+            .collect()
+    }
+
+    /// The full run of in-bounds positions through a position along a direction, without
+    /// wrapping at the edges of the board
+    fn line_through(&self, pos: &[u8], dir: &[i8]) -> Vec<Vec<u8>> {
+        // walk back to the edge of the board
+        let mut start = Vec::from(pos);
+        while let Some(prev) = self.step(&start, dir, -1) {
+            start = prev;
+        }
+
+        let mut line = vec![start];
+        while let Some(next) = self.step(line.last().unwrap(), dir, 1) {
+            line.push(next);
+        }
+
+        line
+    }
+
+    /// The number of pieces in a row needed to win along a direction, the win length or the
+    /// length of the shortest side the direction runs along if that is shorter
+    fn line_length(&self, dir: &[i8]) -> usize {
+        dir.iter()
+            .zip(&self.sizes)
+            .filter(|(d, _)| **d != 0)
+            .map(|(_, size)| usize::from(*size))
+            .chain([self.win_length.into()])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Every winning line through a position along a direction, i.e. every run of positions that
+    /// are the line length long and include the position
+    fn lines_through_dir(&self, pos: &[u8], dir: &[i8]) -> Vec<Vec<Vec<u8>>> {
+        let run = self.line_through(pos, dir);
+        let length = self.line_length(dir);
+
+        run.windows(length)
+            .filter(|line| line.iter().any(|other| other.as_slice() == pos))
+            .map(|line| line.to_vec())
+            .collect()
+    }
+
+    /// Whether a player can still complete a line through a position along a direction
+    fn is_open_line(&self, pos: &[u8], dir: &[i8], player: u8) -> bool {
+        self.lines_through_dir(pos, dir).iter().any(|line| {
+            line.iter().all(|pos| self.cell(pos).is_ok_and(|val| val == 0 || val == player))
+        })
+    }
+
+    /// Whether `player` has any line left on the board that no other player has a piece in
+    pub fn can_still_win(&self, player: u8) -> bool {
+        let directions = Self::directions(self.dimension.into());
+
+        // lines through the player's own pieces are the most likely to be open
+        let owned = self.data.iter()
+            .enumerate()
+            .filter(|(_, val)| **val == player)
+            .map(|(i, _)| i);
+        // otherwise any completely empty line will do
+        let empty = self.data.iter()
+            .enumerate()
+            .filter(|(_, val)| **val == 0)
+            .map(|(i, _)| i);
+
+        owned.chain(empty).any(|i| {
+            let pos = self.position_of(i);
+            directions.iter().any(|dir| self.is_open_line(&pos, dir, player))
+        })
+    }
+
+    /// Whether nobody can win anymore, however the rest of the game goes, because every line has
+    /// pieces of at least two different players in it. The game is then bound to be a draw, even
+    /// if the board isn't full yet.
+    pub fn is_draw_forced(&self) -> bool {
+        self.all_lines().all(|line| {
+            let mut players = line.positions.iter().filter_map(|pos| self.cell(pos).ok().filter(|val| *val != 0));
+            players.next().is_some_and(|first| players.any(|other| other != first))
+        })
+    }
+
+    /// Whether every player with a piece on the board can still win
+    pub fn all_players_can_still_win(&self) -> bool {
+        let mut players: Vec<u8> = self.data.iter()
+            .copied()
+            .filter(|val| *val != 0)
+            .collect();
+        players.sort_unstable();
+        players.dedup();
+
+        players.into_iter().all(|player| self.can_still_win(player))
+    }
+
+    /// Whether a piece could be placed at a position right now, i.e. whether `place_piece` would
+    /// succeed there. Never changes the board.
+    pub fn can_place_at(&self, pos: &[u8]) -> bool {
+        self.cell(pos).is_ok_and(|val| val == 0) && self.is_supported(pos)
+    }
+
+    /// Every position a piece can be placed at right now. The rules are the same for every
+    /// player, so this is the same for any `player`.
+    pub fn legal_moves(&self, _player: u8) -> Vec<Vec<u8>> {
+        (0..self.data.len())
+            .map(|i| self.position_of(i))
+            .filter(|pos| self.can_place_at(pos))
+            .collect()
+    }
+
+    /// Every position a piece can be placed at right now that neighbors a piece already on the
+    /// board, in lexicographic order and each only once. Empty for an empty board, where no move
+    /// is next to anything.
+    pub fn adjacent_empty_positions(&self) -> Vec<Vec<u8>> {
+        let mut positions: Vec<_> = self.occupied_positions()
+            .flat_map(|pos| self.neighbors(&pos))
+            .filter(|pos| self.can_place_at(pos))
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+
+        positions
+    }
+
+    /// The number of leaves of the game tree `depth` moves deep, with `current_player` to move and
+    /// players taking turns from 1 to `num_players`. A game that ends before then is a leaf where
+    /// it ends. Every move is undone again, leaving the board as it was.
+    pub fn perft(&mut self, depth: u8, current_player: u8, num_players: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let next_player = current_player % num_players + 1;
+        let mut leaves = 0;
+        for pos in self.legal_moves(current_player) {
+            let result = self.place_piece_number(current_player, &pos).expect("legal moves can be placed");
+
+            leaves += match result {
+                GameResult::Ongoing => self.perft(depth - 1, next_player, num_players),
+                _ => 1,
+            };
+
+            self.undo_piece_unchecked(&pos).expect("the move was just placed");
+        }
+
+        // a full board is a draw, so there are always moves left at an ongoing position
+        leaves
+    }
+
+    /// The number of different positions reachable in up to `max_depth` moves, with
+    /// `current_player` to move and players taking turns from 1 to `num_players`, the current
+    /// position included. Unlike [`Board::perft`], a position reached by more than one order of
+    /// moves is counted once, told apart by its zobrist hash. Games that end stop there.
+    pub fn reachable_states(&self, current_player: u8, num_players: u8, max_depth: u8) -> u64 {
+        let mut board = self.clone();
+        let mut seen = HashSet::from([self.hash]);
+
+        board.visit_reachable(current_player, num_players, max_depth, &mut seen);
+
+        seen.len() as u64
+    }
+
+    /// Add every position reachable in up to `depth` moves to `seen`, skipping positions already
+    /// in it. Every position the same number of moves away was reached with the same number of
+    /// moves left, so nothing past a skipped position is missed.
+    fn visit_reachable(&mut self, current_player: u8, num_players: u8, depth: u8, seen: &mut HashSet<u64>) {
+        if depth == 0 {
+            return;
+        }
+
+        let next_player = current_player % num_players + 1;
+        for pos in self.legal_moves(current_player) {
+            let result = self.place_piece_number(current_player, &pos).expect("legal moves can be placed");
+
+            if seen.insert(self.hash) && result == GameResult::Ongoing {
+                self.visit_reachable(next_player, num_players, depth - 1, seen);
+            }
+
+            self.undo_piece_unchecked(&pos).expect("the move was just placed");
+        }
+    }
+
+    /// The number of full lines through an empty position that would be `missing` pieces short of
+    /// a win, holding nothing but `player`'s pieces, once `player` places there
+    fn lines_completed_to(&self, pos: &[u8], player: u8, missing: usize) -> usize {
+        Self::directions(self.dimension.into()).iter()
+            .flat_map(|dir| self.lines_through_dir(pos, dir))
+            .filter(|line| {
+                let others = line.iter()
+                    .filter(|other| other.as_slice() != pos)
+                    .map(|other| self.cell(other).unwrap());
+                let mut owned = 0;
+                for val in others {
+                    match val {
+                        0 => {},
+                        val if val == player => owned += 1,
+                        _ => return false,
+                    }
+                }
+                owned + 1 + missing == line.len()
+            })
+            .count()
+    }
+
+    /// Suggest a move for `player` following the standard tic-tac-toe strategy, in order of
+    /// preference:
+    /// 1. win
+    /// 2. block an opponent's win
+    /// 3. fork, creating two lines that each need one more piece
+    /// 4. block an opponent's fork
+    /// 5. the center
+    /// 6. the corner opposite an opponent
+    /// 7. any corner
+    /// 8. any edge, a cell on the boundary in every dimension but one
+    /// 9. anything else
+    ///
+    /// Returns `None` if there is nowhere left to place.
+    pub fn recommended_move(&self, player: u8) -> Option<Vec<u8>> {
+        let moves = self.legal_moves(player);
+
+        let mut opponents: Vec<u8> = self.data.iter()
+            .copied()
+            .filter(|val| *val != 0 && *val != player)
+            .collect();
+        opponents.sort_unstable();
+        opponents.dedup();
+
+        let find = |f: &dyn Fn(&[u8]) -> bool| moves.iter().find(|pos| f(pos)).cloned();
+
+        let is_corner = |pos: &[u8]| pos.iter().zip(&self.sizes).all(|(val, size)| *val == 0 || *val == size - 1);
+        let is_edge = |pos: &[u8]| pos.iter().zip(&self.sizes).filter(|(val, size)| **val != 0 && **val != *size - 1).count() == 1;
+
+        find(&|pos| self.lines_completed_to(pos, player, 0) > 0)
+            .or_else(|| find(&|pos| opponents.iter().any(|opp| self.lines_completed_to(pos, *opp, 0) > 0)))
+            .or_else(|| find(&|pos| self.lines_completed_to(pos, player, 1) > 1))
+            .or_else(|| find(&|pos| opponents.iter().any(|opp| self.lines_completed_to(pos, *opp, 1) > 1)))
+            .or_else(|| find(&|pos| pos.iter().zip(&self.sizes).all(|(val, size)| *val == (size - 1) / 2)))
+            .or_else(|| find(&|pos| {
+                let opposite: Vec<u8> = pos.iter().zip(&self.sizes).map(|(val, size)| size - 1 - val).collect();
+                is_corner(pos) && opponents.contains(&self.cell(&opposite).unwrap())
+            }))
+            .or_else(|| find(&is_corner))
+            .or_else(|| find(&is_edge))
+            .or_else(|| moves.first().cloned())
+    }
+
+    /// Check for a win at a position along a given vector, returning the positions of the
+    /// winning line in order along the vector
+    fn check_win_dir(&self, pos: &[u8], dir: &[i8]) -> Result<Option<Vec<Vec<u8>>>, Error> {
+        // walk away from the position in both directions along the vector, collecting the pieces
+        // in a row that belong to the same player. The position is part of a win if that row
+        // fills the line. The walk stops at the edges of the board rather than wrapping around,
+        // as a wrapped line such as (0,1), (1,2), (2,0) isn't a line on the real board.
+        
+        if pos.len() != dir.len() {
+            return Err(IndexError::OutOfDimension.into());
+        }
+
+        let Some(player) = self.get(pos)? else {
+            return Ok(None);
+        };
+
+        let mut halves = [Vec::new(), Vec::new()];
+        for (half, sign) in halves.iter_mut().zip([-1, 1]) {
+            let mut pos = Vec::from(pos);
+
+            // travel along the direction vector, stopping at the edge of the board
+            while let Some(next) = self.step(&pos, dir, sign) {
+                // check if the position is the player
+                if self.get(&next)? != Some(player) {
+                    break;
+                }
+
+                pos = next;
+                half.push(pos.clone());
+            }
+        }
+
+        let [mut in_a_row, after] = halves;
+        in_a_row.reverse();
+        let index = in_a_row.len(); // where the starting position sits in the row
+        in_a_row.push(Vec::from(pos));
+        in_a_row.extend(after);
+
+        let length = self.line_length(dir);
+        if in_a_row.len() < length {
+            return Ok(None);
+        }
+
+        // the row can be longer than needed to win, in which case take the first winning part of
+        // it with the starting position in it
+        let start = index.saturating_sub(length - 1).min(in_a_row.len() - length);
+        in_a_row.truncate(start + length);
+        in_a_row.drain(..start);
+
+        Ok(Some(in_a_row))
+    }
+
+    /// Whether a position is part of a win along a vector, like [`check_win_dir`] finding a line
+    /// but without building it. The cells of the whole line through the position are gathered
+    /// into a buffer and each window that could win is compared 8 cells at a time as a `u64`, a
+    /// portable stand-in for SIMD that runs on stable Rust.
+    ///
+    /// [`check_win_dir`]: Self::check_win_dir
+    #[cfg(feature = "simd")]
+    pub fn check_win_dir_simd(&self, pos: &[u8], dir: &[i8]) -> Result<bool, Error> {
+        if pos.len() != dir.len() {
+            return Err(IndexError::OutOfDimension.into());
+        }
+
+        let player = self.cell(pos)?;
+        if player == 0 {
+            return Ok(false);
+        }
+
+        // back up to the edge of the board, then gather the line from there to the other edge
+        let mut start = Vec::from(pos);
+        let mut index: usize = 0; // where the position sits in the line
+        while let Some(prev) = self.step(&start, dir, -1) {
+            start = prev;
+            index += 1;
+        }
+
+        let mut cells = vec![self.cell(&start)?];
+        let mut current = start;
+        while let Some(next) = self.step(&current, dir, 1) {
+            cells.push(self.cell(&next)?);
+            current = next;
+        }
+
+        let length = self.line_length(dir);
+        if cells.len() < length {
+            return Ok(false);
+        }
+
+        let first = index.saturating_sub(length - 1);
+        let last = index.min(cells.len() - length);
+        Ok((first..=last).any(|start| Self::all_equal(&cells[start..start + length], player)))
+    }
+
+    /// Whether every cell in a buffer holds `val`, comparing 8 at a time
+    #[cfg(feature = "simd")]
+    fn all_equal(cells: &[u8], val: u8) -> bool {
+        let splat = u64::from_ne_bytes([val; 8]);
+        let mut chunks = cells.chunks_exact(8);
+
+        chunks.by_ref().all(|chunk| u64::from_ne_bytes(chunk.try_into().unwrap()) == splat)
+            && chunks.remainder().iter().all(|cell| *cell == val)
+    }
+}
+
+impl Board {
+    /// The character a cell is displayed as
+    fn cell_symbol(val: u8) -> String {
+        match val {
+            0 => String::from("."),
+            1 => String::from("X"),
+            2 => String::from("O"),
+            player => player.to_string(),
+        }
+    }
+
+    /// The name of an axis in slice headers
+    fn axis_name(axis: usize) -> String {
+        match axis {
+            0 => String::from("x"),
+            1 => String::from("y"),
+            2 => String::from("z"),
+            3 => String::from("w"),
+            axis => format!("d{axis}"),
+        }
+    }
+
+    /// The 2D cross-section of the board through `row_axis` and `col_axis`, indexed as
+    /// `grid[row][col]`. Every other axis is pinned to a coordinate by `fixed_axes`, as
+    /// `(axis, coordinate)` pairs, so there must be exactly `dimension - 2` of them.
+    pub fn get_2d_slice(&self, fixed_axes: &[(usize, u8)], row_axis: usize, col_axis: usize) -> Vec<Vec<u8>> {
+        let dimension = self.sizes.len();
+        assert!(row_axis < dimension && col_axis < dimension, "Slice axes must be among the board's dimensions");
+        assert!(row_axis != col_axis, "Slice needs two different axes");
+        assert!(fixed_axes.len() + 2 == dimension, "Every axis but the slice axes must be fixed");
+
+        let mut pos = vec![0; dimension];
+        for &(axis, val) in fixed_axes {
+            assert!(axis < dimension && axis != row_axis && axis != col_axis, "Fixed axes must be the board's other dimensions");
+            assert!(val < self.sizes[axis], "Fixed coordinate must be on the board");
+            pos[axis] = val;
+        }
+
+        (0..self.sizes[row_axis])
+            .map(|row| {
+                pos[row_axis] = row;
+                (0..self.sizes[col_axis])
+                    .map(|col| {
+                        pos[col_axis] = col;
+                        self.cell(&pos).expect("Slice positions are checked to be on the board")
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Every 2D slice of the board through `row_axis` and `col_axis`, from
+    /// [`get_2d_slice`](Self::get_2d_slice), one after another under a header like
+    /// `axes [2=0, 3=1]:` naming the coordinates the other axes are fixed at
+    pub fn display_all_slices(&self, row_axis: usize, col_axis: usize) -> String {
+        let other_axes: Vec<usize> = (0..self.sizes.len())
+            .filter(|axis| *axis != row_axis && *axis != col_axis)
+            .collect();
+
+        let mut out = String::new();
+        let mut fixed: Vec<(usize, u8)> = other_axes.iter().map(|axis| (*axis, 0)).collect();
+        loop {
+            let header: Vec<String> = fixed.iter().map(|(axis, val)| format!("{axis}={val}")).collect();
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("axes [{}]:\n", header.join(", ")));
+
+            for row in self.get_2d_slice(&fixed, row_axis, col_axis) {
+                let cells: Vec<String> = row.into_iter().map(Self::cell_symbol).collect();
+                out.push_str(&cells.join(" "));
+                out.push('\n');
+            }
+
+            // count up through the fixed coordinates, the last axis fastest
+            let Some(i) = fixed.iter().rposition(|(axis, val)| val + 1 < self.sizes[*axis]) else {
+                break;
+            };
+            fixed[i].1 += 1;
+            for (_, val) in &mut fixed[i + 1..] {
+                *val = 0;
+            }
+        }
+
+        out
+    }
+
+    /// The hyperplane of the board where `axis` is fixed at `index`, shown as its own board of
+    /// one less dimension would be by [`Display`](std::fmt::Display): a 2D grid for a layer of a
+    /// 3D board, or each 2D slice of a bigger layer. The axes after `axis` are numbered one lower
+    /// in the layer's slice headers.
+    pub fn print_layer(&self, axis: usize, index: u8) -> String {
+        let dimension = self.sizes.len();
+        assert!(dimension >= 2, "Layer needs at least 1 dimension left over");
+        assert!(axis < dimension, "Layer axis must be among the board's dimensions");
+        assert!(index < self.sizes[axis], "Layer coordinate must be on the board");
+
+        let mut sizes = self.sizes.clone();
+        sizes.remove(axis);
+        let mut layer = Self::new_rect(&sizes);
+
+        for i in 0..layer.data.len() {
+            let mut pos = layer.position_of(i);
+            pos.insert(axis, index);
+            layer.data[i] = self.cell(&pos).expect("layer positions are on the board");
+        }
+
+        layer.to_string()
+    }
+
+    /// Write each 2D slice of the board, with the text for each cell coming from `cell`, which is
+    /// given the cell's index into data and its value
+    fn write_slices(&self, f: &mut impl std::fmt::Write, cell: impl Fn(usize, u8) -> String) -> std::fmt::Result {
+        let row_length = self.sizes.first().copied().unwrap_or(1).into();
+        let slice_length = self.sizes.iter().take(2).map(|size| usize::from(*size)).product();
+
+        for (i, slice) in self.data.chunks(slice_length).enumerate() {
+            if self.dimension > 2 {
+                let pos = self.position_of(i * slice_length);
+                let header: Vec<String> = pos.iter()
+                    .enumerate()
+                    .skip(2)
+                    .map(|(axis, val)| format!("{}={val}", Self::axis_name(axis)))
+                    .collect();
+
+                if i > 0 {
+                    writeln!(f)?;
+                }
+                writeln!(f, "--- {} ---", header.join(", "))?;
+            }
+
+            for (j, row) in slice.chunks(row_length).enumerate() {
+                let start = i * slice_length + j * row_length;
+                let cells: Vec<String> = row.iter()
+                    .enumerate()
+                    .map(|(k, val)| cell(start + k, *val))
+                    .collect();
+                writeln!(f, "{}", cells.join(" "))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The board as [`Display`](std::fmt::Display) shows it, but with ANSI colors: player 1 in
+    /// red, player 2 in blue, other players cycling through yellow, magenta and cyan, and empty
+    /// cells in gray. A line filled by one player is highlighted in bold green.
+    #[cfg(feature = "color")]
+    pub fn display_colored(&self) -> String {
+        const RESET: &str = "\x1b[0m";
+
+        let winning: Vec<usize> = self.win_lines()
+            .iter()
+            .find(|line| {
+                let player = self.cell(&line[0]).unwrap_or(0);
+                player != 0 && line.iter().all(|pos| self.cell(pos) == Ok(player))
+            })
+            .map(|line| line.iter().filter_map(|pos| self.index_of(pos).ok()).collect())
+            .unwrap_or_default();
+
+        let mut out = String::new();
+        let _ = self.write_slices(&mut out, |index, val| {
+            let color = if winning.contains(&index) {
+                "\x1b[1;32m"
+            } else {
+                match val {
+                    0 => "\x1b[90m",
+                    1 => "\x1b[31m",
+                    2 => "\x1b[34m",
+                    player => ["\x1b[33m", "\x1b[35m", "\x1b[36m"][usize::from(player - 3) % 3],
+                }
+            };
+            format!("{color}{}{RESET}", Self::cell_symbol(val))
+        });
+
+        out
+    }
+
+    /// The board as [`Display`](std::fmt::Display) shows it, since colors need the `color`
+    /// feature
+    #[cfg(not(feature = "color"))]
+    pub fn display_colored(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Shows the board as a grid with `x` along each row and `y` down the columns. Boards with more
+/// than 2 dimensions are shown as each of their 2D slices, under a header with the coordinates of
+/// the slice.
+impl std::fmt::Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_slices(f, |_, val| Self::cell_symbol(val))
+    }
+}
+
+/// The pieces on a board at some point, from [`Board::snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardSnapshot {
+    data: Vec<u8>,
+    hash: u64,
+    column_heights: HashMap<Vec<u8>, u8>,
+}
+
+/// The cells that changed between two boards, from [`Board::diff`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoardDiff {
+    /// Each changed cell as its position, its old value, then its new value
+    pub changes: Vec<(Vec<u8>, u8, u8)>,
+}
+
+/// The value at a position, for positions known to be on the board. Panics if the position can't
+/// be indexed; use [`Board::get`] otherwise.
+impl std::ops::Index<&[u8]> for Board {
+    type Output = u8;
+
+    fn index(&self, pos: &[u8]) -> &Self::Output {
+        match self.index_of(pos) {
+            Ok(index) => &self.data[index],
+            Err(e) => panic!("Can't index board at {pos:?}: {e}"),
+        }
+    }
+}
+
+/// The value at a position, for positions known to be on the board. Panics if the position can't
+/// be indexed. Like [`Board::get_mut`] it writes straight to the cell, bypassing the hash and
+/// gravity bookkeeping that placing a piece does.
+impl std::ops::IndexMut<&[u8]> for Board {
+    fn index_mut(&mut self, pos: &[u8]) -> &mut Self::Output {
+        match self.get_mut(pos) {
+            Ok(val) => val,
+            Err(e) => panic!("Can't index board at {pos:?}: {e}"),
+        }
+    }
+}
+
+/// Sets up a hypercube board a parameter at a time, checking they fit together when it's built.
+/// By default it's a classic 2D 3x3 board, 3 in a row to win, without gravity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardBuilder {
+    dimension: u16,
+    size: u8,
+    win_length: Option<u8>, // the whole side when not given
+    gravity_axis: Option<usize>,
+    mode: GameMode,
+    max_bytes: usize, // boards bigger than this are warned about
+}
+
+impl Default for BoardBuilder {
+    fn default() -> Self {
+        Self {
+            dimension: 2,
+            size: Board::DEFAULT_SIZE,
+            win_length: None,
+            gravity_axis: None,
+            mode: GameMode::Standard,
+            max_bytes: Self::DEFAULT_MAX_BYTES,
+        }
+    }
+}
+
+impl BoardBuilder {
+    const DEFAULT_MAX_BYTES: usize = 1 << 20;
+
+    pub fn new(dimension: u16) -> Self {
+        Self { dimension, ..Self::default() }
+    }
+
+    /// The length of each side
+    pub fn size(mut self, size: u8) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// The number of pieces in a row to win, the length of a side unless given
+    pub fn win_length(mut self, win_length: u8) -> Self {
+        self.win_length = Some(win_length);
+        self
+    }
+
+    /// The axis pieces fall along, if any
+    pub fn gravity_axis(mut self, axis: Option<usize>) -> Self {
+        self.gravity_axis = axis;
+        self
+    }
+
+    pub fn mode(mut self, mode: GameMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The size in bytes past which building a board prints a warning, 1 MiB by default
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn build(&self) -> Result<Board, BuildError> {
+        let win_length = self.win_length.unwrap_or(self.size);
+
+        if self.dimension == 0 {
+            return Err(BuildError::ZeroDimension);
+        }
+        if self.size == 0 {
+            return Err(BuildError::ZeroSize);
+        }
+        if win_length == 0 || win_length > self.size {
+            return Err(BuildError::InvalidWinLength);
+        }
+        if self.gravity_axis.is_some_and(|axis| axis >= self.dimension.into()) {
+            return Err(BuildError::InvalidGravityAxis);
+        }
+
+        let bytes = Board::projected_memory_usage(self.dimension, self.size);
+        if bytes > self.max_bytes {
+            eprintln!("Warning: a {}D board of size {} needs {bytes} bytes, more than the {} allowed",
+                self.dimension, self.size, self.max_bytes);
+        }
+
+        let mut board = Board::try_new_rect(&vec![self.size; self.dimension.into()])?;
+        board.win_length = win_length;
+        board.set_mode(self.mode);
+        board.set_gravity_axis(self.gravity_axis);
+        Ok(board)
+    }
+}
+
+/// A line of positions on a board that wins when filled by one player
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// The positions on the line, in order along the direction
+    pub positions: Vec<Vec<u8>>,
+    pub direction: Direction,
+}
+
+/// Every winning line on a board, see [`Board::all_lines`]
+#[derive(Debug, Clone)]
+pub struct LineIter<'a> {
+    board: &'a Board,
+    directions: Vec<Direction>,
+    dir: usize, // the direction the lines are currently running in
+    index: usize, // the index into data of the next position to start a line at
+}
+
+impl Iterator for LineIter<'_> {
+    type Item = Line;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let board = self.board;
+
+        while let Some(dir) = self.directions.get(self.dir) {
+            if self.index >= board.data.len() {
+                self.dir += 1;
+                self.index = 0;
+                continue;
+            }
+
+            let start = board.position_of(self.index);
+            self.index += 1;
+
+            // follow the direction from the start, skipping starts too close to the edge
+            let positions = std::iter::successors(Some(start), |pos| board.step(pos, dir, 1))
+                .take(board.line_length(dir))
+                .collect::<Vec<_>>();
+            if positions.len() == board.line_length(dir) {
+                return Some(Line { positions, direction: dir.clone() });
+            }
+        }
+
+        None
+    }
+}
+
+/// Every position on a board in lexicographic order, from `[0, 0, ...]` to the far corner. Only
+/// the shape of the board is borrowed, not its cells.
+#[derive(Debug, Clone)]
+pub struct PositionIter<'a> {
+    sizes: &'a [u8],
+    next: Option<Vec<u8>>,
+}
+
+impl Iterator for PositionIter<'_> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+
+        // count up at the last axis, carrying into the axes before it
+        let mut next = current.clone();
+        for (val, size) in next.iter_mut().zip(self.sizes).rev() {
+            *val += 1;
+            if *val < *size {
+                self.next = Some(next);
+                break;
+            }
+            *val = 0;
+        }
+
+        Some(current)
+    }
+}
+
+/// The shape of a board and its pieces as they are written out by serde
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct SerializeBoard<'b> {
+    dimension: u16,
+    sizes: &'b [u8],
+    win_length: u8,
+    mode: GameMode,
+    gravity_axis: Option<usize>,
+    data: &'b [u8],
+}
+
+/// A board as it is read back in by serde, before it is checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct DeserializeBoard {
+    dimension: u16,
+    sizes: Vec<u8>,
+    win_length: u8,
+    #[serde(default)]
+    mode: GameMode,
+    #[serde(default)]
+    gravity_axis: Option<usize>,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeBoard {
+            dimension: self.dimension,
+            sizes: &self.sizes,
+            win_length: self.win_length,
+            mode: self.mode,
+            gravity_axis: self.gravity_axis,
+            data: &self.data,
+        }.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let saved = DeserializeBoard::deserialize(deserializer)?;
+
+        if usize::from(saved.dimension) != saved.sizes.len() {
+            return Err(D::Error::custom("dimension doesn't match the number of sizes"));
+        }
+        if saved.dimension == 0 {
+            return Err(D::Error::custom("board needs at least 1 dimension"));
+        }
+        if saved.sizes.contains(&0) {
+            return Err(D::Error::custom("board size must be at least 1"));
+        }
+        if saved.win_length == 0 || saved.win_length > saved.sizes.iter().copied().max().unwrap_or(1) {
+            return Err(D::Error::custom("win length must be between 1 and the board size"));
+        }
+        if saved.gravity_axis.is_some_and(|axis| axis >= saved.sizes.len()) {
+            return Err(D::Error::custom("gravity axis must be one of the board's dimensions"));
+        }
+        if Self::get_data_length(&saved.sizes) != Some(saved.data.len()) {
+            return Err(D::Error::custom("data doesn't fit the board size"));
+        }
+
+        let mut board = Self::new_rect(&saved.sizes);
+        board.win_length = saved.win_length;
+        board.mode = saved.mode;
+        board.gravity_axis = saved.gravity_axis;
+        board.data.copy_from_slice(&saved.data);
+
+        // neither the hash nor the column heights are saved, so work them out from the pieces
+        board.recompute_zobrist_hash();
+        board.recompute_column_heights();
+
+        Ok(board)
+    }
+}
+
+/// A game in progress: a board along with the players taking turns on it
+#[derive(Debug)]
+pub struct Game {
+    board: Board,
+    num_players: u8,
+    current_player: u8, // players are numbered from 1, 0 is reserved for empty cells
+    history: Vec<Vec<u8>>, // every position played, in order
+    state: GameResult, // the state after the last move
+    repetitions: HashMap<u64, usize>, // how many times each position's zobrist hash has come up
+    repetition_threshold: usize,
+}
+
+impl Game {
+    /// The number of times a position has to come up for the game to be drawn, unless changed
+    /// with [`Game::set_repetition_threshold`]
+    pub const DEFAULT_REPETITION_THRESHOLD: usize = 3;
+
+    /// Create a classic sized game between `num_players` players
+    ///
+    /// # Panics
+    /// If `num_players` is 0, like every other way of creating a game
+    pub fn new(dimension: u16, num_players: u8) -> Self {
+        Self::with_mode(dimension, num_players, GameMode::Standard)
+    }
+
+    /// Create a game played under misère rules, see [`GameMode::Misere`]
+    pub fn new_misere(dimension: u16, num_players: u8) -> Self {
+        Self::with_mode(dimension, num_players, GameMode::Misere)
+    }
+
+    /// Create a game played under Wild rules, see [`GameMode::Wild`]
+    pub fn new_wild(dimension: u16, num_players: u8) -> Self {
+        Self::with_mode(dimension, num_players, GameMode::Wild)
+    }
+
+    fn with_mode(dimension: u16, num_players: u8, mode: GameMode) -> Self {
+        let mut board = Board::new_with_size(dimension, Board::DEFAULT_SIZE);
+        board.set_mode(mode);
+
+        Self::with_board(board, num_players)
+    }
+
+    /// Start a game on a board that has already been set up, e.g. with gravity. The board's mode
+    /// is the game's mode.
+    ///
+    /// # Panics
+    /// If `num_players` is 0, as there would be nobody to take a turn
+    pub fn with_board(mut board: Board, num_players: u8) -> Self {
+        assert!(num_players > 0, "A game needs at least 1 player");
+
+        // the board may have been set up by writing to its cells directly
+        board.recompute_zobrist_hash();
+        board.recompute_column_heights();
+        let repetitions = HashMap::from([(board.zobrist_hash(), 1)]);
+
+        Self {
+            board,
+            num_players,
+            current_player: 1,
+            history: Vec::new(),
+            state: GameResult::Ongoing,
+            repetitions,
+            repetition_threshold: Self::DEFAULT_REPETITION_THRESHOLD,
+        }
+    }
+
+    /// Draw the game once a position has come up `threshold` times, the starting position
+    /// included
+    pub fn set_repetition_threshold(&mut self, threshold: usize) {
+        self.repetition_threshold = threshold;
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn mode(&self) -> GameMode {
+        self.board.mode()
+    }
+
+    pub fn num_players(&self) -> u8 {
+        self.num_players
+    }
+
+    /// The player whose turn it is
+    pub fn current_player(&self) -> u8 {
+        self.current_player
+    }
+
+    /// The state of the game after the last move
+    pub fn state(&self) -> GameResult {
+        self.state
+    }
+
+    /// The number of moves played so far
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Every position played so far, in order
+    pub fn history(&self) -> &[Vec<u8>] {
+        &self.history
+    }
+
+    /// The player after the current one in turn order
+    fn next_player(&self) -> u8 {
+        self.current_player % self.num_players + 1
+    }
+
+    /// The player before the current one in turn order
+    fn previous_player(&self) -> u8 {
+        (self.current_player + self.num_players - 2) % self.num_players + 1
+    }
+
+    /// Record a move and pass the turn on to the next player
+    fn end_turn(&mut self, pos: &[u8], state: GameResult) -> GameResult {
+        self.history.push(Vec::from(pos));
+
+        // the same check as Board::check_draw_by_repetition on a history with this position pushed,
+        // with the hashes already counted
+        let count = self.repetitions.entry(self.board.zobrist_hash()).or_default();
+        *count += 1;
+        let repeated = *count >= self.repetition_threshold;
+
+        self.state = if state == GameResult::Ongoing && repeated { GameResult::Draw } else { state };
+        self.current_player = self.next_player();
+
+        self.debug_validate();
+        self.state
+    }
+
+    /// In debug builds, check the board's invariants still hold and that every piece belongs to
+    /// one of the players
+    fn debug_validate(&self) {
+        debug_assert!(self.board.validate_invariants().is_ok(), "{}", self.board.validate_invariants().unwrap_err());
+        debug_assert!(self.board.data.iter().all(|val| *val <= self.num_players), "piece of a player not in the game");
+    }
+
+    /// Clear the board and history to start the game over from the first player
+    pub fn reset(&mut self) {
+        self.board.reset();
+        self.history.clear();
+        self.state = GameResult::Ongoing;
+        self.repetitions = HashMap::from([(self.board.zobrist_hash(), 1)]);
+        self.current_player = 1;
+    }
+
+    /// Take back the last move, handing the turn back to the player who made it. The game is
+    /// always back in play afterwards, as it must have been for the move to be made. The position
+    /// taken back still counts towards a draw by repetition, so playing the same move over and
+    /// over after undoing it draws the game.
+    pub fn undo_move(&mut self) -> Result<(), UndoError> {
+        let pos = self.history.pop().ok_or(UndoError::NothingToUndo)?;
+
+        // the last move can't be supporting any other piece
+        self.board.undo_piece_unchecked(&pos)
+            .expect("moves in the history are on the board");
+
+        self.state = GameResult::Ongoing;
+        self.current_player = self.previous_player();
+        self.debug_validate();
+
+        Ok(())
+    }
+
+    /// Place a piece for the current player, record the move, and pass the turn on. A completed line is a win for
+    /// the current player, or under misère rules a loss. Once the game is over every move fails with
+    /// [`PlaceError::GameOver`].
+    pub fn play(&mut self, pos: &[u8]) -> Result<GameResult, Error> {
+        if self.state != GameResult::Ongoing {
+            return Err(PlaceError::GameOver.into());
+        }
+
+        let state = self.board.place_piece_number(self.current_player, pos)?;
+
+        Ok(self.end_turn(pos, state))
+    }
+
+    /// Place `piece_player`'s piece for the current player. Only allowed in a Wild game, and only
+    /// with a piece belonging to one of the players.
+    pub fn play_wild(&mut self, piece_player: u8, pos: &[u8]) -> Result<GameResult, Error> {
+        if self.state != GameResult::Ongoing {
+            return Err(PlaceError::GameOver.into());
+        }
+        if self.mode() != GameMode::Wild || piece_player == 0 || piece_player > self.num_players {
+            return Err(PlaceError::InvalidPiece.into());
+        }
+
+        let state = self.board.place_any_piece(self.current_player, piece_player, pos)?;
+
+        Ok(self.end_turn(pos, state))
+    }
+}
+
+/// Every move of a game along with the board it was played on, enough to play it again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    /// The length of the board along each dimension
+    pub sizes: Vec<u8>,
+    pub win_length: u8,
+    pub mode: GameMode,
+    pub gravity_axis: Option<usize>,
+    pub num_players: u8,
+    /// The player whose piece was placed and where, in order. Outside Wild games that's always
+    /// the player who moved.
+    pub moves: Vec<(u8, Vec<u8>)>,
+}
+
+impl GameRecord {
+    /// A record of a game that hasn't started yet, on a board from [`Game::new`]
+    pub fn new(dimension: u16, num_players: u8) -> Self {
+        Self {
+            sizes: vec![Board::DEFAULT_SIZE; dimension.into()],
+            win_length: Board::DEFAULT_SIZE,
+            mode: GameMode::Standard,
+            gravity_axis: None,
+            num_players,
+            moves: Vec::new(),
+        }
+    }
+
+    /// The number of dimensions of the board
+    pub fn dimension(&self) -> u16 {
+        self.sizes.len() as u16
+    }
+
+    /// Record the moves played so far in a game, and the board they were played on
+    pub fn record_from_game(game: &Game) -> Self {
+        let board = game.board();
+        let moves = game.history()
+            .iter()
+            .map(|pos| (board[pos.as_slice()], pos.clone()))
+            .collect();
+
+        Self {
+            sizes: Vec::from(board.sizes()),
+            win_length: board.win_length(),
+            mode: board.mode(),
+            gravity_axis: board.gravity_axis(),
+            num_players: game.num_players(),
+            moves,
+        }
+    }
+
+    /// An empty board set up the way the record's game was played. Errors if no board could be.
+    pub fn board(&self) -> Result<Board, BuildError> {
+        let mut board = Board::try_new_rect(&self.sizes)?;
+
+        if self.win_length == 0 || self.win_length > self.sizes.iter().copied().max().unwrap_or(0) {
+            return Err(BuildError::InvalidWinLength);
+        }
+        if self.gravity_axis.is_some_and(|axis| axis >= self.sizes.len()) {
+            return Err(BuildError::InvalidGravityAxis);
+        }
+
+        board.win_length = self.win_length;
+        board.set_mode(self.mode);
+        board.set_gravity_axis(self.gravity_axis);
+        Ok(board)
+    }
+
+    /// Play the moves again on a new game on the record's board. Errors if the board can't be
+    /// set up or a move can't be placed, or with `InvalidPiece` if there are no players or,
+    /// outside Wild games, a move was recorded for a player out of turn.
+    pub fn replay(&self) -> Result<Game, Error> {
+        if self.num_players == 0 {
+            return Err(PlaceError::InvalidPiece.into());
+        }
+
+        let mut game = Game::with_board(self.board()?, self.num_players);
+
+        for (player, pos) in &self.moves {
+            if self.mode == GameMode::Wild {
+                game.play_wild(*player, pos)?;
+                continue;
+            }
+
+            if *player != game.current_player() {
+                return Err(PlaceError::InvalidPiece.into());
+            }
+
+            game.play(pos)?;
+        }
+
+        Ok(game)
+    }
+
+    /// The parts of the board that differ from a board from [`Game::new`], as their names in
+    /// [`GameRecord::to_pgn_like`] headers with their values
+    fn board_options(&self) -> Vec<(&'static str, String)> {
+        let mut options = Vec::new();
+
+        if self.sizes.iter().any(|size| *size != Board::DEFAULT_SIZE) {
+            options.push(("Sizes", self.sizes.iter().map(u8::to_string).collect::<Vec<_>>().join(",")));
+        }
+        if self.win_length != self.sizes.iter().copied().max().unwrap_or(0) {
+            options.push(("WinLength", self.win_length.to_string()));
+        }
+        match self.mode {
+            GameMode::Standard => {},
+            GameMode::Misere => options.push(("Mode", String::from("misere"))),
+            GameMode::Wild => options.push(("Mode", String::from("wild"))),
+        }
+        if let Some(axis) = self.gravity_axis {
+            options.push(("Gravity", axis.to_string()));
+        }
+
+        options
+    }
+
+    /// Set the part of the board named `key` from [`GameRecord::board_options`], in any case and
+    /// with or without underscores. Returns false for any other key.
+    fn set_board_option(&mut self, key: &str, value: &str) -> Result<bool, ParseError> {
+        match key.replace('_', "").to_ascii_lowercase().as_str() {
+            "sizes" => {
+                let sizes: Position = value.parse()?;
+                if sizes.len() != self.sizes.len() {
+                    return Err(ParseError::InvalidFormat);
+                }
+                // a win length that wasn't given is the longest side
+                if self.win_length == self.sizes.iter().copied().max().unwrap_or(0) {
+                    self.win_length = sizes.iter().copied().max().unwrap_or(0);
+                }
+                self.sizes = sizes.into();
+            },
+            "winlength" => self.win_length = value.trim().parse()?,
+            "mode" => self.mode = match value.trim().to_ascii_lowercase().as_str() {
+                "standard" => GameMode::Standard,
+                "misere" => GameMode::Misere,
+                "wild" => GameMode::Wild,
+                _ => return Err(ParseError::InvalidFormat),
+            },
+            "gravity" => self.gravity_axis = Some(value.trim().parse()?),
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Write the record as text: a `dimension players` header line followed by a line for each
+    /// move with the player whose piece it was and the position, e.g. `1: 0,2`. Any way the board
+    /// differs from a board from [`Game::new`] follows on the header line, e.g.
+    /// `3 2 gravity=2` or `2 2 sizes=4,4 win_length=3`.
+    pub fn to_notation(&self) -> String {
+        let mut notation = format!("{} {}", self.dimension(), self.num_players);
+        for (key, value) in self.board_options() {
+            let key = if key == "WinLength" { String::from("win_length") } else { key.to_ascii_lowercase() };
+            notation.push_str(&format!(" {key}={value}"));
+        }
+        notation.push('\n');
+
+        for (player, pos) in &self.moves {
+            let pos = pos.iter().map(u8::to_string).collect::<Vec<_>>().join(",");
+            notation.push_str(&format!("{player}: {pos}\n"));
+        }
+
+        notation
+    }
+
+    /// Read a record back from the text written by [`GameRecord::to_notation`]. Blank lines are
+    /// ignored.
+    pub fn from_notation(s: &str) -> Result<Self, ParseError> {
+        let mut lines = s.lines().map(str::trim).filter(|line| !line.is_empty());
+
+        let mut header = lines.next().ok_or(ParseError::InvalidFormat)?.split_whitespace();
+        let dimension = header.next().ok_or(ParseError::InvalidFormat)?.parse()?;
+        let num_players = header.next().ok_or(ParseError::InvalidFormat)?.parse()?;
+
+        let mut record = Self::new(dimension, num_players);
+        for option in header {
+            let (key, value) = option.split_once('=').ok_or(ParseError::InvalidFormat)?;
+            if !record.set_board_option(key, value)? {
+                return Err(ParseError::InvalidFormat);
+            }
+        }
+
+        record.moves = lines
+            .map(|line| {
+                let (player, pos) = line.split_once(':').ok_or(ParseError::InvalidFormat)?;
+                let pos: Position = pos.parse()?;
+
+                Ok((player.trim().parse()?, pos.into()))
+            })
+            .collect::<Result<_, ParseError>>()?;
+
+        Ok(record)
+    }
+
+    /// Write the record in a format borrowed from chess's PGN: a line of `[Key "value"]` headers
+    /// followed by the moves, numbered a round of every player at a time, e.g.
+    /// `[Dimension "2"] [Players "2"] [Result "1-0"]` then `1. (0,0) (1,1) 2. (2,0)`. Headers for
+    /// `Sizes`, `WinLength`, `Mode` and `Gravity` are added for boards that differ from a board
+    /// from [`Game::new`], and in Wild games each move has the piece placed before it, e.g.
+    /// `2@(1,1)`.
+    ///
+    /// The result comes from replaying the game, `1-0` when player 1 won, `0-1` when player 2
+    /// did, `1/2-1/2` for a draw, and `*` for anything else, such as an unfinished game or a win
+    /// by player 3. In a two player misère game, the player who completed a line loses to the
+    /// other.
+    pub fn to_pgn_like(&self) -> String {
+        let two_players = self.num_players == 2;
+        let result = match self.replay().map(|game| game.state()) {
+            Ok(GameResult::Win(1)) => "1-0",
+            Ok(GameResult::Win(2)) => "0-1",
+            Ok(GameResult::Loss(1)) if two_players => "0-1",
+            Ok(GameResult::Loss(2)) if two_players => "1-0",
+            Ok(GameResult::Draw) => "1/2-1/2",
+            _ => "*",
+        };
+
+        let mut headers = vec![
+            (String::from("Dimension"), self.dimension().to_string()),
+            (String::from("Players"), self.num_players.to_string()),
+            (String::from("Result"), String::from(result)),
+        ];
+        headers.extend(self.board_options().into_iter().map(|(key, value)| (String::from(key), value)));
+
+        let mut pgn = headers.iter()
+            .map(|(key, value)| format!("[{key} \"{value}\"]"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        pgn.push('\n');
+
+        let round_length = usize::from(self.num_players.max(1));
+        let rounds = self.moves.chunks(round_length)
+            .enumerate()
+            .map(|(round, moves)| {
+                let moves = moves.iter()
+                    .map(|(player, pos)| {
+                        let pos = pos.iter().map(u8::to_string).collect::<Vec<_>>().join(",");
+                        if self.mode == GameMode::Wild { format!("{player}@({pos})") } else { format!("({pos})") }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                format!("{}. {moves}", round + 1)
+            })
+            .collect::<Vec<_>>();
+        pgn.push_str(&rounds.join(" "));
+        pgn.push('\n');
+
+        pgn
+    }
+
+    /// Read a record back from the text written by [`GameRecord::to_pgn_like`]. The `Dimension`
+    /// and `Players` headers are needed and any unknown ones are ignored. Moves without a piece
+    /// before them are given to the players in turn, and whitespace anywhere between the pieces
+    /// doesn't matter.
+    pub fn from_pgn_like(s: &str) -> Result<Self, ParseError> {
+        let mut rest = s.trim_start();
+        let mut dimension = None;
+        let mut num_players = None;
+        let mut options = Vec::new();
+
+        while let Some(header) = rest.strip_prefix('[') {
+            let (header, after) = header.split_once(']').ok_or(ParseError::InvalidFormat)?;
+            let (key, value) = header.trim().split_once(char::is_whitespace).ok_or(ParseError::InvalidFormat)?;
+            let value = value.trim()
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .ok_or(ParseError::InvalidFormat)?;
+
+            match key {
+                "Dimension" => dimension = Some(value.trim().parse()?),
+                "Players" => num_players = Some(value.trim().parse::<u8>()?),
+                _ => options.push((key, value)),
+            }
+            rest = after.trim_start();
+        }
+
+        let (Some(dimension), Some(num_players)) = (dimension, num_players) else {
+            return Err(ParseError::InvalidFormat);
+        };
+
+        let mut record = Self::new(dimension, num_players);
+        // sizes first, as they decide the win length when it isn't given
+        options.sort_by_key(|(key, _)| *key != "Sizes");
+        for (key, value) in options {
+            record.set_board_option(key, value)?;
+        }
+
+        while !rest.is_empty() {
+            if let Some(pos) = rest.strip_prefix('(') {
+                let (pos, after) = pos.split_once(')').ok_or(ParseError::InvalidFormat)?;
+                let pos: Position = pos.parse()?;
+
+                let player = (record.moves.len() % usize::from(num_players.max(1))) as u8 + 1;
+                record.moves.push((player, pos.into()));
+                rest = after;
+            } else {
+                // a move number, or the piece placed in a Wild game
+                let end = rest.find(['.', '@']).ok_or(ParseError::InvalidFormat)?;
+                let number = rest[..end].trim();
+
+                if rest[end..].starts_with('@') {
+                    let pos = rest[end + 1..].trim_start().strip_prefix('(').ok_or(ParseError::InvalidFormat)?;
+                    let (pos, after) = pos.split_once(')').ok_or(ParseError::InvalidFormat)?;
+                    let pos: Position = pos.parse()?;
+
+                    record.moves.push((number.parse()?, pos.into()));
+                    rest = after;
+                } else {
+                    number.parse::<usize>()?;
+                    rest = &rest[end + 1..];
+                }
+            }
+            rest = rest.trim_start();
+        }
+
+        Ok(record)
+    }
+}
+
+/// Results of many games, e.g. a tournament between computer players
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GameStats {
+    pub num_games: u32,
+    /// The number of games each player won, leaving out players who haven't won any
+    pub wins_per_player: HashMap<u8, u32>,
+    pub draws: u32,
+    /// The number of moves played over every game
+    pub total_moves: u64,
+}
+
+impl GameStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a game that ended with `result` after `move_count` moves. Only wins and draws are
+    /// counted by result, so a misère loss or an unfinished game adds to the games and moves
+    /// alone.
+    pub fn record_game(&mut self, result: GameResult, move_count: usize) {
+        self.num_games += 1;
+        self.total_moves += move_count as u64;
+
+        match result {
+            GameResult::Win(player) => *self.wins_per_player.entry(player).or_default() += 1,
+            GameResult::Draw => self.draws += 1,
+            GameResult::Loss(_) | GameResult::Ongoing => {},
+        }
+    }
+
+    /// The fraction of games `player` won, 0 before any games are recorded
+    pub fn win_rate(&self, player: u8) -> f64 {
+        if self.num_games == 0 {
+            return 0.0;
+        }
+
+        let wins = self.wins_per_player.get(&player).copied().unwrap_or(0);
+        f64::from(wins) / f64::from(self.num_games)
+    }
+
+    /// The number of moves in a game on average, 0 before any games are recorded
+    pub fn avg_moves(&self) -> f64 {
+        if self.num_games == 0 {
+            return 0.0;
+        }
+
+        self.total_moves as f64 / f64::from(self.num_games)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_board() {
+        let board = Board::new(3).unwrap();
+        let expected = [0_u8; 3_usize.pow(3)];
+        let actual = &board.data;
+        
+        // board data with expected data
+        assert!(expected.iter()
+            .zip(actual.iter())
+            .all(|(a, b)| {a == b}) 
+        );
+    }
+
+    #[test]
+    fn get() {
+        let mut board = Board::new(4).unwrap();
+        let expected = 4;
+
+        // 0 1 2 |  9 10 11 | 18 19 20 \
+        // 3 4 5 | 12 13 14 | 21 22 23 |
+        // 6 7 8 | 15 16 17 | 24 25 26 / 1st 3d slice of 4d tic tac toe, first item in 2nd slice will be index 27
+        board.data[27] = expected; // directly set value
+        let acutal = board.get(&[0,0,0,1]).unwrap(); // get previously set position
+
+        assert_eq!(acutal, Player::new(expected));
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut board = Board::new(6).unwrap();
+        let expected = 7;
+        let pos = [0,0,0,0,2,0];
+
+        *board.get_mut(&pos).unwrap() = expected;
+
+        let actual = board.get(&pos).unwrap();
+
+        assert_eq!(actual, Player::new(expected));
+    }
+
+    #[test]
+    fn valid_placement() {
+        let mut board = Board::new(3).unwrap();
+        let expected = [
+            0,0,0,1,0,0,0,0,0,
+            0,0,0,1,0,0,0,0,0,
+        ];
+
+        board.place_piece(Player::P1, &[0,1,0]).unwrap();
+        board.place_piece(Player::P1, &[0,1,1]).unwrap();
+
+        // compare board data with expected data
+        assert!(expected.iter()
+            .zip(board.data.iter())
+            .all(|(a, b)| {a == b}) 
+        );
+    }
+
+    #[test]
+    fn unsupported_placement() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        let actual = board.place_piece(Player::P1, &[0,1,1]).unwrap_err();
+
+        assert_eq!(actual, Error::PlaceError(PlaceError::Unsupported));
+    }
+
+    #[test]
+    fn occupied_placement() {
+        let mut board = Board::new(3).unwrap();
+
+        board.place_piece(Player::P1, &[0,1,0]).unwrap();
+        let actual = board.place_piece(Player::P1, &[0,1,0]).unwrap_err();
+
+        assert_eq!(actual, Error::PlaceError(PlaceError::Occupied));
+    }
+    
+    #[test]
+    fn win_dir_straight() {
+        let mut board = Board::new(3).unwrap();
+        let expected = Some(vec![vec![0,0,0], vec![0,1,0], vec![0,2,0]]);
+        
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P1, &[0,1,0]).unwrap();
+        board.place_piece(Player::P1, &[0,2,0]).unwrap();
+
+        let actual = board.check_win_dir(&[0,0,0], &[0,1,0]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+    
+    #[test]
+    fn win_dir_diag() {
+        let mut board = Board::new(3).unwrap();
+        let expected = Some(vec![vec![0,0,0], vec![1,1,0], vec![2,2,0]]);
+        
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,1,0]).unwrap();
+        board.place_piece(Player::P1, &[2,2,0]).unwrap();
+
+        let actual = board.check_win_dir(&[0,0,0], &[1,1,0]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn win_dir_loop() {
+        let mut board = Board::new(3).unwrap();
+        let expected = Some(vec![vec![0,0,0], vec![1,1,0], vec![2,2,0]]);
+        
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,1,0]).unwrap();
+        board.place_piece(Player::P1, &[2,2,0]).unwrap();
+
+        let actual = board.check_win_dir(&[2,2,0], &[1,1,0]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn win_dir_no_win() {
+        let mut board = Board::new(3).unwrap();
+        let expected = None;
+        
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+
+        let actual = board.check_win_dir(&[0,0,0], &[1,1,0]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn win_no_win() {
+        let mut board = Board::new(2).unwrap();
+        let expected = GameResult::Ongoing;
+
+        let actual = board.place_piece(Player::P1, &[0,2]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn win_straight() {
+        let mut board = Board::new(2).unwrap();
+        let expected = GameResult::Win(1);
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[0,1]).unwrap();
+        let actual = board.place_piece(Player::P1, &[0,2]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn win_diag() {
+        let mut board = Board::new(2).unwrap();
+        let expected = GameResult::Win(1);
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+        let actual = board.place_piece(Player::P1, &[2,2]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn misere_diag_loses() {
+        let mut board = Board::new(2).unwrap();
+        let expected = GameResult::Loss(1);
+
+        board.play_misere(1, &[0,0]).unwrap();
+        board.play_misere(1, &[1,1]).unwrap();
+        let actual = board.play_misere(1, &[2,2]).unwrap();
+
+        assert_eq!(actual, expected);
+
+        // the same on a board already played under misère rules, with any number of players
+        let mut board = Board::new(2).unwrap();
+        board.set_mode(GameMode::Misere);
+        for pos in [[0,0], [1,1]] {
+            board.play_misere(3, &pos).unwrap();
+        }
+        assert_eq!(board.play_misere(3, &[2,2]), Ok(GameResult::Loss(3)));
+    }
+
+    #[test]
+    fn misere_game_diag_loses() {
+        let mut game = Game::new_misere(2, 2);
+        let expected = GameResult::Loss(1);
+
+        game.play(&[0,0]).unwrap(); // 1
+        game.play(&[0,1]).unwrap(); // 2
+        game.play(&[1,1]).unwrap(); // 1
+        game.play(&[0,2]).unwrap(); // 2
+        let actual = game.play(&[2,2]).unwrap(); // 1 completes the diagonal
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn standard_game_diag_wins() {
+        let mut game = Game::new(2, 2);
+        let expected = GameResult::Win(1);
+
+        game.play(&[0,0]).unwrap();
+        game.play(&[0,1]).unwrap();
+        game.play(&[1,1]).unwrap();
+        game.play(&[0,2]).unwrap();
+        let actual = game.play(&[2,2]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn wild_complete_opponent_line() {
+        let mut board = Board::new(2).unwrap();
+        let expected = GameResult::Win(2);
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[0,1]).unwrap();
+        // player 2 finishes player 1's row and takes the win for themself
+        let actual = board.place_any_piece(2, 1, &[0,2]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn wild_game_steal_win() {
+        let mut game = Game::new_wild(2, 2);
+        let expected = GameResult::Win(2);
+
+        game.play(&[0,0]).unwrap(); // 1 places X
+        game.play_wild(1, &[0,1]).unwrap(); // 2 places X, a blunder in standard play
+        game.play(&[2,2]).unwrap(); // 1 places X elsewhere
+        let actual = game.play_wild(1, &[0,2]).unwrap(); // 2 completes the row of X
+
+        assert_eq!(actual, expected);
+        assert_eq!(game.play_wild(2, &[1,1]), Err(PlaceError::GameOver.into()));
+    }
+
+    #[test]
+    fn wild_only_in_wild_game() {
+        let mut game = Game::new(2, 2);
+
+        let actual = game.play_wild(2, &[0,0]).unwrap_err();
+
+        assert_eq!(actual, Error::PlaceError(PlaceError::InvalidPiece));
+    }
+
+    #[test]
+    fn directions_count() {
+        let expected = (3_usize.pow(4) - 1) / 2;
+
+        let actual = Board::all_directions(4).len();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn directions_2d() {
+        let expected: Vec<Direction> = vec![vec![0,1], vec![1,-1], vec![1,0], vec![1,1]];
+
+        let actual = Board::all_directions(2);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(feature = "cached-directions")]
+    #[test]
+    fn directions_cached() {
+        for dimension in 1..6 {
+            let expected = Board::all_directions(dimension);
+
+            let actual = Board::all_directions_cached(dimension);
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn valid_board_state() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        board.place_piece(Player::P1, &[0,1,0]).unwrap();
+        board.place_piece(Player::P2, &[0,1,1]).unwrap();
+
+        assert!(board.is_valid_board_state());
+        assert!(board.run_self_check().is_empty());
+    }
+
+    #[test]
+    fn invalid_board_state() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        *board.data.last_mut().unwrap() = 1; // floating piece at (2,2,2)
+
+        assert!(!board.is_valid_board_state());
+        assert_eq!(board.run_self_check().len(), 1);
+    }
+
+    #[test]
+    fn board_state_out_of_range() {
+        let mut board = Board::new(2).unwrap();
+        board.data.fill(7);
+
+        assert!(!board.is_valid_board_state());
+        let problems = board.run_self_check();
+        assert_eq!(problems.len(), 7);
+        assert_eq!(problems[0], "piece of player 7 on the board but none of player 1");
+
+        // pieces of any player can be placed in Wild games
+        board.set_mode(GameMode::Wild);
+        assert!(board.is_valid_board_state());
+    }
+
+    #[test]
+    fn board_state_out_of_turn() {
+        let mut board = Board::new(2).unwrap();
+        for pos in [[0,0], [2,0], [1,2], [0,1], [2,2]] {
+            board.place_piece(Player::P1, &pos).unwrap();
+        }
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+
+        // player 1 has 5 pieces to player 2's 1, with no line anywhere
+        assert_eq!(board.any_winner(), None);
+        assert!(!board.is_valid_board_state());
+        assert_eq!(board.run_self_check(), ["player 2 has 1 pieces, out of turn with player 1's 5"]);
+
+        for pos in [[1,0], [0,2], [2,1]] {
+            board.place_piece(Player::P2, &pos).unwrap();
+        }
+        assert!(board.is_valid_board_state());
+
+        // skipping player 2's turn for player 3
+        board.undo_piece_unchecked(&[2,1]).unwrap();
+        board.place_piece(Player::P3, &[2,1]).unwrap();
+        assert!(!board.is_valid_board_state());
+    }
+
+    #[test]
+    #[should_panic(expected = "none of player 1")]
+    fn assert_valid_out_of_range() {
+        let mut board = Board::new(2).unwrap();
+        *board.get_mut(&[1,1]).unwrap() = 2;
+
+        board.assert_valid();
+    }
+
+    #[test]
+    fn undo_piece_unchecked() {
+        let mut board = Board::new(2).unwrap();
+
+        board.place_piece(Player::P2, &[1,2]).unwrap();
+        let removed = board.undo_piece_unchecked(&[1,2]).unwrap();
+
+        assert_eq!(removed, 2);
+        assert_eq!(board.get(&[1,2]).unwrap(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "not supported")]
+    #[cfg(debug_assertions)]
+    fn undo_supporting_piece() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        board.place_piece(Player::P1, &[0,1,0]).unwrap();
+        board.place_piece(Player::P1, &[0,1,1]).unwrap();
+        board.undo_piece_unchecked(&[0,1,0]).unwrap();
+    }
+
+    #[test]
+    fn can_still_win_empty() {
+        let board = Board::new(2).unwrap();
+
+        assert!(board.can_still_win(1));
+        assert!(board.all_players_can_still_win());
+    }
+
+    #[test]
+    fn can_still_win_blocked() {
+        let mut board = Board::new(2).unwrap();
+
+        // 1 2 1
+        // 2 2 1
+        // 1 1 2
+        for (player, pos) in [(1,[0,0]),(2,[1,0]),(1,[2,0]),(2,[0,1]),(2,[1,1]),(1,[2,1]),(1,[0,2]),(1,[1,2]),(2,[2,2])] {
+            *board.get_mut(&pos).unwrap() = player;
+        }
+
+        assert!(!board.can_still_win(1));
+        assert!(!board.can_still_win(2));
+        assert!(!board.all_players_can_still_win());
+    }
+
+    #[test]
+    fn can_still_win_one_open_line() {
+        let mut board = Board::new(2).unwrap();
+
+        // 1 2 .
+        // 2 1 .
+        // . . .
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+        board.place_piece(Player::P2, &[0,1]).unwrap();
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+
+        assert!(board.can_still_win(1));
+        assert!(board.can_still_win(2)); // the third row and column are still empty
+        assert!(board.all_players_can_still_win());
+    }
+
+    #[test]
+    fn recommended_win() {
+        let mut board = Board::new(2).unwrap();
+        let expected = Some(vec![2,0]);
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,0]).unwrap();
+        board.place_piece(Player::P2, &[0,1]).unwrap();
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+
+        let actual = board.recommended_move(1); // winning beats blocking at (2,1)
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn recommended_block() {
+        let mut board = Board::new(2).unwrap();
+        let expected = Some(vec![2,1]);
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,1]).unwrap();
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+
+        let actual = board.recommended_move(1);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn recommended_fork() {
+        let mut board = Board::new(2).unwrap();
+        let expected = Some(vec![1,1]);
+
+        // 1 2 .
+        // . x 1  <- the center lines up with both of 1's pieces, as does (2,2)
+        // 2 . .
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+        board.place_piece(Player::P1, &[2,1]).unwrap();
+        board.place_piece(Player::P2, &[0,2]).unwrap();
+
+        let actual = board.recommended_move(1);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn recommended_block_fork() {
+        let mut board = Board::new(2).unwrap();
+        let expected = Some(vec![0,1]);
+
+        // 2 1 .
+        // x . 2  <- 2 could fork here, at the center, or at (2,2)
+        // . . .
+        board.place_piece(Player::P2, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,0]).unwrap();
+        board.place_piece(Player::P2, &[2,1]).unwrap();
+
+        let actual = board.recommended_move(1);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn recommended_center() {
+        let board = Board::new(2).unwrap();
+        let expected = Some(vec![1,1]);
+
+        let actual = board.recommended_move(1);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn recommended_opposite_corner() {
+        let mut board = Board::new(2).unwrap();
+        let expected = Some(vec![0,2]);
+
+        board.place_piece(Player::P2, &[2,0]).unwrap();
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+
+        let actual = board.recommended_move(1);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn recommended_corner() {
+        let mut board = Board::new(2).unwrap();
+        let expected = Some(vec![0,0]);
+
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+
+        let actual = board.recommended_move(1);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn recommended_edge() {
+        // 1 2 3 1 2
+        // 3 . . . .
+        // . . 1 . .
+        // . . . . .
+        // 2 . . . 3
+        // with the center and corners taken and no threats, the first empty cell (1,1) is inside
+        // the board but (4,1) is on its edge
+        let mut board = Board::new_with_size(2, 5);
+        let pieces = [
+            (1, [0,0]), (2, [1,0]), (3, [2,0]), (1, [3,0]), (2, [4,0]),
+            (3, [0,1]), (1, [2,2]), (2, [0,4]), (3, [4,4]),
+        ];
+        for (player, pos) in pieces {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        let actual = board.recommended_move(1);
+
+        assert_eq!(actual, Some(vec![4,1]));
+    }
+
+    #[test]
+    fn recommended_full() {
+        let mut board = Board::new(1).unwrap();
+
+        board.place_piece(Player::P1, &[0]).unwrap();
+        board.place_piece(Player::P2, &[1]).unwrap();
+        board.place_piece(Player::P1, &[2]).unwrap();
+
+        assert_eq!(board.recommended_move(2), None);
+    }
+
+    #[test]
+    fn create_sized_board() {
+        let board = Board::new_with_size(3, 4);
+
+        assert_eq!(board.size(), 4);
+        assert_eq!(board.data.len(), 4_usize.pow(3));
+    }
+
+    #[test]
+    fn get_sized() {
+        let mut board = Board::new_with_size(2, 4);
+        let expected = 2;
+
+        *board.get_mut(&[3,1]).unwrap() = expected;
+
+        assert_eq!(board.data[7], expected); // 3 + 4*1
+        assert_eq!(board.get(&[3,1]).unwrap(), Player::new(expected));
+    }
+
+    #[test]
+    fn win_sized_needs_size_in_a_row() {
+        let mut board = Board::new_with_size(2, 4);
+
+        assert_eq!(board.place_piece(Player::P1, &[0,0]).unwrap(), GameResult::Ongoing);
+        assert_eq!(board.place_piece(Player::P1, &[1,1]).unwrap(), GameResult::Ongoing);
+        assert_eq!(board.place_piece(Player::P1, &[2,2]).unwrap(), GameResult::Ongoing); // 3 in a row isn't enough anymore
+        assert_eq!(board.place_piece(Player::P1, &[3,3]).unwrap(), GameResult::Win(1));
+    }
+
+    #[test]
+    fn create_rect_board() {
+        let board = Board::new_rect(&[3,4,2]);
+
+        assert_eq!(board.dimension, 3);
+        assert_eq!(board.sizes(), &[3,4,2]);
+        assert_eq!(board.data.len(), 3*4*2);
+    }
+
+    #[test]
+    fn get_rect() {
+        let mut board = Board::new_rect(&[3,4]);
+        let expected = 5;
+
+        *board.get_mut(&[2,3]).unwrap() = expected;
+
+        assert_eq!(board.data[11], expected); // 2 + 3*3
+        assert_eq!(board.get(&[2,3]).unwrap(), Player::new(expected));
+    }
+
+    #[test]
+    fn win_rect_long_side() {
+        let mut board = Board::new_rect(&[3,4]);
+
+        assert_eq!(board.place_piece(Player::P1, &[0,0]).unwrap(), GameResult::Ongoing);
+        assert_eq!(board.place_piece(Player::P1, &[0,1]).unwrap(), GameResult::Ongoing);
+        assert_eq!(board.place_piece(Player::P1, &[0,2]).unwrap(), GameResult::Ongoing); // the long side takes 4 in a row
+        assert_eq!(board.place_piece(Player::P1, &[0,3]).unwrap(), GameResult::Win(1));
+    }
+
+    #[test]
+    fn win_rect_short_side() {
+        let mut board = Board::new_rect(&[3,4]);
+
+        board.place_piece(Player::P1, &[0,3]).unwrap();
+        board.place_piece(Player::P1, &[1,3]).unwrap();
+        let actual = board.place_piece(Player::P1, &[2,3]).unwrap();
+
+        assert_eq!(actual, GameResult::Win(1));
+    }
+
+    #[test]
+    fn win_rect_diag() {
+        let mut board = Board::new_rect(&[3,4]);
+
+        board.place_piece(Player::P1, &[0,1]).unwrap();
+        board.place_piece(Player::P1, &[1,2]).unwrap();
+        let actual = board.place_piece(Player::P1, &[2,3]).unwrap(); // diagonals span the short side
+
+        assert_eq!(actual, GameResult::Win(1));
+    }
+
+    #[test]
+    fn no_win_wrapped_diag() {
+        let mut board = Board::new(2).unwrap();
+
+        board.place_piece(Player::P1, &[0,1]).unwrap();
+        board.place_piece(Player::P1, &[1,2]).unwrap();
+        let actual = board.place_piece(Player::P1, &[2,0]).unwrap();
+
+        assert_eq!(actual, GameResult::Ongoing);
+    }
+
+    #[test]
+    fn clone_independent() {
+        let mut board = Board::new(3).unwrap();
+        board.place_piece(Player::P1, &[0,1,0]).unwrap();
+        board.place_piece(Player::P2, &[2,2,0]).unwrap();
+        let expected = Vec::from(&*board.data);
+
+        let mut clone = board.clone();
+        clone.place_piece(Player::P1, &[0,1,1]).unwrap();
+        *clone.get_mut(&[2,2,0]).unwrap() = 0;
+
+        assert_eq!(&*board.data, expected.as_slice());
+        assert_eq!(clone.get(&[0,1,1]).unwrap(), Some(Player::P1));
+        assert_eq!(clone.get(&[0,1,0]).unwrap(), Some(Player::P1));
+    }
+
+    #[test]
+    fn display_2d() {
+        let mut board = Board::new(2).unwrap();
+        let expected = "X . .\n. O .\n. . 3\n";
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+        board.place_piece(Player::P3, &[2,2]).unwrap();
+
+        assert_eq!(board.to_string(), expected);
+    }
+
+    #[test]
+    fn display_4d() {
+        let mut board = Board::new(4).unwrap();
+
+        *board.get_mut(&[1,0,0,1]).unwrap() = 1;
+        let actual = board.to_string();
+
+        assert_eq!(actual.matches("---").count(), 2 * 9);
+        assert!(actual.starts_with("--- z=0, w=0 ---\n. . .\n"));
+        assert!(actual.contains("--- z=0, w=1 ---\n. X .\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn display_colored_players() {
+        let mut board = Board::new(2).unwrap();
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+        let actual = board.display_colored();
+
+        assert!(actual.starts_with("\x1b[31mX\x1b[0m \x1b[34mO\x1b[0m \x1b[90m.\x1b[0m\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn display_colored_winning_line() {
+        let mut board = Board::new(2).unwrap();
+
+        for pos in [[0,0], [1,1], [2,2]] {
+            board.place_piece(Player::P1, &pos).unwrap();
+        }
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+        let actual = board.display_colored();
+
+        assert_eq!(actual.matches("\x1b[1;32mX").count(), 3);
+        assert!(actual.contains("\x1b[34mO"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "color"))]
+    fn display_colored_plain() {
+        let mut board = Board::new(2).unwrap();
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+
+        assert_eq!(board.display_colored(), board.to_string());
+    }
+
+    #[test]
+    fn get_2d_slice() {
+        let mut board = Board::new(4).unwrap();
+
+        board.place_piece(Player::P1, &[2,0,1,0]).unwrap();
+        board.place_piece(Player::P2, &[1,2,0,0]).unwrap();
+
+        let slice = board.get_2d_slice(&[(2, 1), (3, 0)], 0, 1);
+        assert_eq!(slice, vec![vec![0, 0, 0], vec![0, 0, 0], vec![1, 0, 0]]);
+
+        // the other piece is in a different slice, and swapping the axes transposes it
+        let slice = board.get_2d_slice(&[(2, 0), (3, 0)], 1, 0);
+        assert_eq!(slice, vec![vec![0, 0, 0], vec![0, 0, 0], vec![0, 2, 0]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_2d_slice_missing_fixed_axis() {
+        let board = Board::new(4).unwrap();
+
+        board.get_2d_slice(&[(2, 1)], 0, 1);
+    }
+
+    #[test]
+    fn print_layer() {
+        let mut board = Board::new(3).unwrap();
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P2, &[2,1,0]).unwrap();
+        board.place_piece(Player::P1, &[1,1,1]).unwrap();
+
+        assert_eq!(board.print_layer(2, 0), "X . .\n. . O\n. . .\n");
+        assert_eq!(board.print_layer(2, 1), ". . .\n. X .\n. . .\n");
+
+        // fixing x leaves y along the rows and z down the columns
+        assert_eq!(board.print_layer(0, 2), ". O .\n. . .\n. . .\n");
+    }
+
+    #[test]
+    fn print_layer_4d() {
+        let mut board = Board::new(4).unwrap();
+        board.place_piece(Player::P2, &[1,2,0,2]).unwrap();
+
+        let layer = board.print_layer(2, 0);
+        assert_eq!(layer.matches("---").count(), 6); // a header for each of 3 slices
+        assert_eq!(layer.matches('O').count(), 1);
+        assert!(board.print_layer(2, 1).chars().all(|c| c != 'O'));
+    }
+
+    #[test]
+    fn display_all_slices_3d() {
+        let mut board = Board::new(3).unwrap();
+
+        board.place_piece(Player::P1, &[0,1,2]).unwrap();
+        let actual = board.display_all_slices(0, 1);
+
+        assert_eq!(actual.matches("axes [").count(), 3);
+        assert!(actual.starts_with("axes [2=0]:\n. . .\n"));
+        assert!(actual.contains("axes [2=2]:\n. X .\n"));
+    }
+
+    #[test]
+    fn display_all_slices_4d() {
+        let board = Board::new(4).unwrap();
+
+        let actual = board.display_all_slices(1, 3);
+
+        assert_eq!(actual.matches("axes [").count(), 9);
+        assert!(actual.contains("axes [0=2, 2=1]:\n"));
+    }
+
+    #[test]
+    fn project_onto_subspace() {
+        let mut board = Board::new(3).unwrap();
+
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,0,2]).unwrap();
+        board.place_piece(Player::P2, &[1,2,1]).unwrap();
+        let projected = board.project_onto_subspace(&[0, 1]);
+
+        assert_eq!(projected.dimension, 2);
+        for pos in projected.positions() {
+            let occupied = (0..3).any(|z| board.get(&[pos[0], pos[1], z]).unwrap().is_some());
+            assert_eq!(projected.get(&pos).unwrap(), occupied.then_some(Player::P1));
+        }
+        assert_eq!(projected.count_pieces_for_player(1), 2);
+    }
+
+    #[test]
+    fn embed_sub_board() {
+        let mut board = Board::new(3).unwrap();
+        let mut sub = Board::new(2).unwrap();
+
+        sub.place_piece(Player::P1, &[0,1]).unwrap();
+        sub.place_piece(Player::P2, &[2,2]).unwrap();
+        board.embed_sub_board(&sub, &[0, 1], &[2]).unwrap();
+
+        assert_eq!(board.get(&[0,1,2]).unwrap(), Some(Player::P1));
+        assert_eq!(board.get(&[2,2,2]).unwrap(), Some(Player::P2));
+        assert_eq!(board.count_empty_cells(), 25);
+        assert_eq!(board.project_onto_subspace(&[0, 1]).count_pieces_for_player(1), 2);
+    }
+
+    #[test]
+    fn embed_sub_board_mismatched() {
+        let mut board = Board::new(3).unwrap();
+
+        let actual = board.embed_sub_board(&Board::new(2).unwrap(), &[0, 1], &[]).unwrap_err();
+        assert_eq!(actual, IndexError::OutOfDimension);
+
+        let actual = board.embed_sub_board(&Board::new(2).unwrap(), &[0, 0], &[1]).unwrap_err();
+        assert_eq!(actual, IndexError::OutOfDimension);
+
+        let actual = board.embed_sub_board(&Board::new(2).unwrap(), &[0, 2], &[3]).unwrap_err();
+        assert_eq!(actual, IndexError::OutOfBounds);
+
+        let actual = board.embed_sub_board(&Board::new_rect(&[3, 2]), &[0, 2], &[1]).unwrap_err();
+        assert_eq!(actual, IndexError::OutOfBounds);
+    }
+
+    #[test]
+    fn permute_axes_inverse() {
+        let mut board = Board::new_rect(&[2, 3, 4]);
+        let perm = [2, 0, 1];
+        let inverse = [1, 2, 0];
+
+        board.place_piece(Player::P1, &[1,0,3]).unwrap();
+        board.place_piece(Player::P2, &[0,2,1]).unwrap();
+        let permuted = board.permute_axes(&perm).unwrap();
+
+        assert_eq!(permuted.sizes(), &[4, 2, 3]);
+        assert_eq!(permuted.get(&[3,1,0]).unwrap(), Some(Player::P1));
+        assert_eq!(permuted.get(&[1,0,2]).unwrap(), Some(Player::P2));
+
+        let restored = permuted.permute_axes(&inverse).unwrap();
+        assert_eq!(restored.sizes(), board.sizes());
+        assert_eq!(restored.data, board.data);
+        assert_eq!(restored.zobrist_hash(), board.zobrist_hash());
+    }
+
+    #[test]
+    fn permute_axes_invalid() {
+        let board = Board::new(3).unwrap();
+
+        assert_eq!(board.permute_axes(&[0, 1]).unwrap_err(), IndexError::OutOfDimension);
+        assert_eq!(board.permute_axes(&[0, 1, 1]).unwrap_err(), IndexError::OutOfDimension);
+        assert_eq!(board.permute_axes(&[0, 1, 3]).unwrap_err(), IndexError::OutOfDimension);
+    }
+
+    #[test]
+    fn get_just_out_of_bounds() {
+        let mut board = Board::new(2).unwrap();
+        let expected = IndexError::OutOfBounds;
+
+        assert_eq!(board.get(&[3,0]).unwrap_err(), expected);
+        assert_eq!(board.get(&[0,3]).unwrap_err(), expected);
+        assert_eq!(board.get_mut(&[3,3]).unwrap_err(), expected);
+    }
+
+    #[test]
+    fn new_zero_dimension() {
+        assert_eq!(Board::new(0).unwrap_err(), BuildError::ZeroDimension);
+    }
+
+    #[test]
+    fn new_too_large() {
+        // 3^65535 cells can't even be counted, and 3^40 can be but not allocated
+        assert_eq!(Board::new(u16::MAX).unwrap_err(), BuildError::TooLarge);
+        assert_eq!(Board::new(40).unwrap_err(), BuildError::TooLarge);
+        assert_eq!(BoardBuilder::new(u16::MAX).build().unwrap_err(), BuildError::TooLarge);
+    }
+
+    #[test]
+    fn builder_default() {
+        let board = BoardBuilder::default().build().unwrap();
+
+        assert_eq!(board.sizes(), &[3, 3]);
+        assert_eq!(board.win_length, 3);
+        assert_eq!(board.mode(), GameMode::Standard);
+        assert_eq!(board.gravity_axis(), None);
+    }
+
+    #[test]
+    fn builder_options() {
+        let board = BoardBuilder::new(3)
+            .size(5)
+            .win_length(4)
+            .gravity_axis(Some(2))
+            .mode(GameMode::Misere)
+            .build()
+            .unwrap();
+
+        assert_eq!(board.sizes(), &[5, 5, 5]);
+        assert_eq!(board.win_length, 4);
+        assert_eq!(board.mode(), GameMode::Misere);
+        assert_eq!(board.gravity_axis(), Some(2));
+    }
+
+    #[test]
+    fn builder_errors() {
+        assert_eq!(BoardBuilder::new(0).build().unwrap_err(), BuildError::ZeroDimension);
+        assert_eq!(BoardBuilder::new(2).size(0).build().unwrap_err(), BuildError::ZeroSize);
+        assert_eq!(BoardBuilder::new(2).win_length(4).build().unwrap_err(), BuildError::InvalidWinLength);
+        assert_eq!(BoardBuilder::new(2).win_length(0).build().unwrap_err(), BuildError::InvalidWinLength);
+        assert_eq!(BoardBuilder::new(2).gravity_axis(Some(2)).build().unwrap_err(), BuildError::InvalidGravityAxis);
+    }
+
+    #[test]
+    fn index_read_write() {
+        let mut board = Board::new(3).unwrap();
+        let pos = [1,2,0];
+
+        board.place_piece(Player::P2, &[0,0,0]).unwrap();
+        board[&pos[..]] = 1;
+
+        assert_eq!(board[&pos[..]], 1);
+        assert_eq!(board.get(&pos).unwrap(), Some(Player::P1));
+        assert_eq!(board[&[0,0,0][..]], 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't index board")]
+    fn index_out_of_bounds() {
+        let board = Board::new(2).unwrap();
+
+        let _ = board[&[3,0][..]];
+    }
+
+    #[test]
+    fn snapshot_restore() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        board.place_piece(Player::P1, &[1,1,0]).unwrap();
+        let expected = board.clone();
+        let snapshot = board.snapshot();
+
+        for (player, pos) in [(2, [1,1,1]), (1, [0,0,0]), (2, [0,0,1]), (1, [1,1,2])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+        board.restore_snapshot(snapshot);
+
+        assert_eq!(board.data, expected.data);
+        assert_eq!(board.zobrist_hash(), expected.zobrist_hash());
+        assert_eq!(board.drop_position(&[1,1]), Ok(vec![1,1,1]));
+    }
+
+    #[test]
+    fn diff_apply() {
+        let mut before = Board::new(3).unwrap();
+        before.place_piece(Player::P1, &[0,0,0]).unwrap();
+        before.place_piece(Player::P2, &[1,1,1]).unwrap();
+
+        let mut after = before.clone();
+        after.place_piece(Player::P1, &[2,0,1]).unwrap();
+        after.undo_piece_unchecked(&[1,1,1]).unwrap();
+
+        let diff = before.diff(&after).unwrap();
+        assert_eq!(diff.changes, vec![(vec![2,0,1], 0, 1), (vec![1,1,1], 2, 0)]);
+
+        before.apply_diff(&diff).unwrap();
+        assert_eq!(before.data, after.data);
+        assert_eq!(before.zobrist_hash(), after.zobrist_hash());
+    }
+
+    #[test]
+    fn diff_mismatched() {
+        let board = Board::new(3).unwrap();
+
+        assert_eq!(board.diff(&Board::new(2).unwrap()).unwrap_err(), IndexError::OutOfDimension.into());
+        assert_eq!(board.diff(&Board::new_rect(&[3, 3, 4])).unwrap_err(), IndexError::OutOfBounds.into());
+
+        let mut other = board.clone();
+        let diff = BoardDiff { changes: vec![(vec![0,0,0], 0, 1), (vec![0,0], 0, 1)] };
+        assert!(other.apply_diff(&diff).is_err());
+        assert_eq!(other.data, board.data);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn diff_serde_round_trip() {
+        let diff = BoardDiff { changes: vec![(vec![0,2], 0, 1)] };
+
+        let json = serde_json::to_string(&diff).unwrap();
+
+        assert_eq!(serde_json::from_str::<BoardDiff>(&json).unwrap(), diff);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn win_parallel_matches_sequential() {
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = || {
+            // xorshift, enough to scatter pieces around
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        for _ in 0..20 {
+            let mut board = Board::new(4).unwrap();
+            for val in board.data.iter_mut() {
+                *val = (next() % 3) as u8;
+            }
+
+            for pos in board.positions() {
+                assert_eq!(board.find_win_line_parallel(&pos), board.find_win_line(&pos));
+            }
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn check_win_dir_simd_matches_scalar() {
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        // long lines, so some of them fill more than one 8 cell chunk
+        let mut board = Board::new_with_win_length(2, 20, 10);
+        for _ in 0..10 {
+            for val in board.data.iter_mut() {
+                // mostly player 1, so there are some long rows
+                *val = if next() % 8 == 0 { 2 } else { 1 };
+            }
+
+            for pos in board.positions() {
+                for dir in Board::all_directions(2) {
+                    let expected = board.check_win_dir(&pos, &dir).unwrap().is_some();
+                    assert_eq!(board.check_win_dir_simd(&pos, &dir).unwrap(), expected);
+                }
+            }
+        }
+
+        for _ in 0..10 {
+            let mut board = Board::new(3).unwrap();
+            for val in board.data.iter_mut() {
+                *val = (next() % 3) as u8;
+            }
+
+            for pos in board.positions() {
+                for dir in Board::all_directions(3) {
+                    let expected = board.check_win_dir(&pos, &dir).unwrap().is_some();
+                    assert_eq!(board.check_win_dir_simd(&pos, &dir).unwrap(), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn memory_usage() {
+        assert_eq!(Board::new(4).unwrap().memory_usage(), 81);
+        assert_eq!(Board::new_rect(&[2, 5]).memory_usage(), 10);
+        assert_eq!(Board::projected_memory_usage(8, 3), 6561);
+        assert_eq!(Board::projected_memory_usage(255, 255), usize::MAX);
+
+        // over the limit still builds, with a warning
+        let board = BoardBuilder::new(3).max_bytes(10).build().unwrap();
+        assert_eq!(board.memory_usage(), 27);
+    }
+
+    #[test]
+    fn board_is_static() {
+        fn assert_static<T: 'static + Send + Sync>() {}
+        assert_static::<Board>();
+
+        // boards can be kept in a map or shared without borrowing from anything
+        let mut boards = HashMap::new();
+        boards.insert(1, std::sync::Arc::new(Board::new(2).unwrap()));
+        assert_eq!(boards[&1].memory_usage(), 9);
+    }
+
+    #[test]
+    fn more_than_255_dimensions() {
+        let mut board = Board::new_with_size(300, 1);
+        let pos = vec![0; 300];
+
+        assert_eq!(board.dimension, 300);
+        assert_eq!(board.memory_usage(), 1);
+        *board.get_mut(&pos).unwrap() = 1;
+
+        let bytes = board.to_bytes();
+        assert_eq!(bytes[..2], 300_u16.to_le_bytes());
+        let actual = Board::from_bytes(&bytes).unwrap();
+        assert_eq!(actual.dimension, 300);
+        assert_eq!(actual.get(&pos), Ok(Some(Player::P1)));
+    }
+
+    #[test]
+    fn data_length_overflow() {
+        assert_eq!(Board::get_data_length(&[3; 9]), Some(19683));
+        assert_eq!(Board::get_data_length(&[255; 200]), None);
+    }
+
+    #[test]
+    fn game_result_and_terminal_state() {
+        let mut board = Board::new(2).unwrap();
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        assert_eq!(board.game_result(&[0,0]), Ok(GameResult::Ongoing));
+        assert_eq!(board.is_terminal_state(&[0,0]), Ok(false));
+
+        board.place_piece(Player::P1, &[1,0]).unwrap();
+        board.place_piece(Player::P1, &[2,0]).unwrap();
+        assert_eq!(board.game_result(&[2,0]), Ok(GameResult::Win(1)));
+        assert_eq!(board.is_terminal_state(&[2,0]), Ok(true));
+
+        // X X O
+        // O O X
+        // X X O
+        let mut full = Board::new(2).unwrap();
+        for (i, player) in [1, 1, 2, 2, 2, 1, 1, 1, 2].into_iter().enumerate() {
+            full.data[i] = player;
+        }
+        assert_eq!(full.game_result(&[2,2]), Ok(GameResult::Draw));
+        assert_eq!(full.is_terminal_state(&[2,2]), Ok(true));
+        assert!(full.is_terminal_state(&[0,0,0]).is_err());
+    }
+
+    #[test]
+    fn reachable_states() {
+        let board = Board::new(2).unwrap();
+
+        assert_eq!(board.reachable_states(1, 2, 0), 1);
+        assert_eq!(board.reachable_states(1, 2, 1), 1 + 9);
+        // the two pieces differ, so every order of the first two moves is its own position
+        assert_eq!(board.reachable_states(1, 2, 2), 1 + 9 + 72);
+        // two of player 1's pieces can be placed in either order, giving the same position
+        assert_eq!(board.reachable_states(1, 2, 3), 1 + 9 + 72 + 252);
+        // every position of the classic game, stopping at wins
+        assert_eq!(board.reachable_states(1, 2, 9), 5478);
+    }
+
+    #[test]
+    fn perft_2d() {
+        let mut board = Board::new(2).unwrap();
+
+        assert_eq!(board.perft(0, 1, 2), 1);
+        assert_eq!(board.perft(1, 1, 2), 9);
+        assert_eq!(board.perft(2, 1, 2), 72);
+        assert_eq!(board.perft(4, 1, 2), 3024);
+        // 1440 games are won on the 5th move and the rest go on to a 6th
+        assert_eq!(board.perft(6, 1, 2), 1440 + (15120 - 1440) * 4);
+        // every game of tic-tac-toe
+        assert_eq!(board.perft(9, 1, 2), 255168);
+        assert_eq!(board.count_empty_cells(), 9);
+
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+        assert_eq!(board.perft(1, 2, 2), board.count_empty_cells() as u64);
+    }
+
+    #[test]
+    fn validate_invariants() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,0,1]).unwrap();
+        board.undo_piece_unchecked(&[0,0,1]).unwrap();
+        assert_eq!(board.validate_invariants(), Ok(()));
+
+        // a piece written straight to a cell skips the hash and column heights
+        let mut written = board.clone();
+        *written.get_mut(&[1,1,0]).unwrap() = 2;
+        assert!(written.validate_invariants().unwrap_err().contains("column heights"));
+
+        let mut written = Board::new(2).unwrap();
+        *written.get_mut(&[1,1]).unwrap() = 2;
+        assert!(written.validate_invariants().unwrap_err().contains("zobrist hash"));
+
+        let mut floating = board.clone();
+        *floating.get_mut(&[2,2,2]).unwrap() = 1;
+        assert!(floating.validate_invariants().unwrap_err().contains("not supported"));
+    }
+
+    #[test]
+    fn player_numbers() {
+        assert_eq!(Player::try_from(2), Ok(Player::P2));
+        assert_eq!(u8::from(Player::P3), 3);
+        assert_eq!(Player::new(200).map(Player::number), Some(200));
+        assert_eq!(Player::new(0), None);
+        assert_eq!(Player::P1.to_string(), "1");
+    }
+
+    #[test]
+    fn has_unsupported_piece() {
+        let mut board = Board::new_with_gravity(3, 2);
+        assert!(!board.has_unsupported_piece());
+
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,0,1]).unwrap();
+        assert!(!board.has_unsupported_piece());
+
+        // loaded straight into the cells, skipping the gravity check
+        *board.get_mut(&[1,1,1]).unwrap() = 1;
+        assert!(board.has_unsupported_piece());
+        assert!(!board.is_valid_board_state());
+
+        let mut floating = Board::new(3).unwrap();
+        *floating.get_mut(&[1,1,1]).unwrap() = 1;
+        assert!(!floating.has_unsupported_piece());
+    }
+
+    #[test]
+    fn place_empty_piece() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        assert_eq!(Player::try_from(0), Err(PlaceError::InvalidPiece));
+        assert_eq!(board.place_piece_number(0, &[0,0,0]), Err(PlaceError::InvalidPiece.into()));
+        assert_eq!(board.validate_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn get_out_of_dimension() {
+        let board = Board::new(3).unwrap();
+        let expected = IndexError::OutOfDimension;
+
+        let actual = board.get(&[0,0]).unwrap_err();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn place_out_of_dimension() {
+        let mut board = Board::new(2).unwrap();
+        let expected = Error::IndexError(IndexError::OutOfDimension);
+
+        let actual = board.place_piece(Player::P1, &[0,0,0]).unwrap_err();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn errors_compare_by_variant() {
+        assert_ne!(Error::from(PlaceError::Occupied), Error::from(PlaceError::Unsupported));
+        assert_ne!(Error::from(IndexError::OutOfBounds), Error::from(IndexError::OutOfDimension));
+    }
+
+    #[test]
+    fn is_full() {
+        let mut board = Board::new(1).unwrap();
+
+        board.place_piece(Player::P1, &[0]).unwrap();
+        board.place_piece(Player::P2, &[1]).unwrap();
+        assert!(!board.is_full());
+
+        board.place_piece(Player::P1, &[2]).unwrap();
+        assert!(board.is_full());
+    }
+
+    #[test]
+    fn draw() {
+        let mut board = Board::new(2).unwrap();
+        let expected = GameResult::Draw;
+
+        // X O X
+        // X O O
+        // O X X
+        let moves = [[0,0],[1,0],[2,0],[1,1],[0,1],[2,1],[1,2],[0,2]];
+        for (i, pos) in moves.iter().enumerate() {
+            let state = board.place_piece_number(i as u8 % 2 + 1, pos).unwrap();
+            assert_eq!(state, GameResult::Ongoing);
+        }
+        let actual = board.place_piece(Player::P1, &[2,2]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn game_turns() {
+        let mut game = Game::new(2, 3);
+
+        assert_eq!(game.current_player(), 1);
+        assert_eq!(game.move_count(), 0);
+
+        game.play(&[0,0]).unwrap();
+        game.play(&[1,0]).unwrap();
+        assert_eq!(game.current_player(), 3);
+
+        game.play(&[2,0]).unwrap();
+        assert_eq!(game.current_player(), 1);
+        assert_eq!(game.move_count(), 3);
+        assert_eq!(game.history(), &[vec![0,0], vec![1,0], vec![2,0]]);
+        assert_eq!(game.board().get(&[1,0]).unwrap(), Some(Player::P2));
+    }
+
+    #[test]
+    fn game_failed_move_keeps_turn() {
+        let mut game = Game::new(2, 2);
+
+        game.play(&[0,0]).unwrap();
+        game.play(&[0,0]).unwrap_err();
+
+        assert_eq!(game.current_player(), 2);
+        assert_eq!(game.move_count(), 1);
+    }
+
+    #[test]
+    fn game_over_rejects_moves() {
+        let mut game = Game::new(2, 2);
+
+        for pos in [[0,0], [0,1], [1,0], [1,1]] {
+            game.play(&pos).unwrap();
+        }
+        assert_eq!(game.play(&[2,0]).unwrap(), GameResult::Win(1));
+
+        assert_eq!(game.play(&[2,1]), Err(PlaceError::GameOver.into()));
+        assert_eq!(game.move_count(), 5);
+        assert_eq!(game.board().get(&[2,1]).unwrap(), None);
+    }
+
+    #[test]
+    fn undo_move() {
+        let mut game = Game::new(2, 2);
+
+        game.play(&[0,0]).unwrap();
+        game.play(&[1,1]).unwrap();
+        game.undo_move().unwrap();
+
+        assert_eq!(game.current_player(), 2);
+        assert_eq!(game.move_count(), 1);
+        assert_eq!(game.board().get(&[1,1]).unwrap(), None);
+        assert_eq!(game.board().get(&[0,0]).unwrap(), Some(Player::P1));
+    }
+
+    #[test]
+    fn undo_win() {
+        let mut game = Game::new(2, 2);
+
+        for pos in [[0,0],[0,1],[1,1],[0,2]] {
+            game.play(&pos).unwrap();
+        }
+        assert_eq!(game.play(&[2,2]).unwrap(), GameResult::Win(1));
+        assert_eq!(game.state(), GameResult::Win(1));
+
+        game.undo_move().unwrap();
+
+        assert_eq!(game.state(), GameResult::Ongoing);
+        assert_eq!(game.current_player(), 1);
+    }
+
+    #[test]
+    fn undo_nothing() {
+        let mut game = Game::new(2, 2);
+        let expected = UndoError::NothingToUndo;
+
+        let actual = game.undo_move().unwrap_err();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn can_place_at() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        assert!(board.can_place_at(&[0,1,0]));
+        assert!(!board.can_place_at(&[0,1,1])); // unsupported
+        assert!(!board.can_place_at(&[0,1])); // can't be indexed
+
+        board.place_piece(Player::P1, &[0,1,0]).unwrap();
+
+        assert!(!board.can_place_at(&[0,1,0])); // occupied
+        assert!(board.can_place_at(&[0,1,1]));
+    }
+
+    #[test]
+    fn legal_moves_decrease() {
+        let mut board = Board::new(2).unwrap();
+
+        for (i, pos) in [[0,0],[1,1],[2,1]].iter().enumerate() {
+            assert_eq!(board.legal_moves(1).len(), 9 - i);
+            board.place_piece(Player::P1, pos).unwrap();
+        }
+
+        assert_eq!(board.legal_moves(1).len(), 6);
+        assert!(!board.legal_moves(1).contains(&vec![1,1]));
+    }
+
+    #[test]
+    fn legal_moves_gravity() {
+        let board = Board::new_with_gravity(3, 2);
+
+        // only the bottom layer is supported on an empty board
+        assert_eq!(board.legal_moves(1).len(), 9);
+        assert_eq!(Board::new(3).unwrap().legal_moves(1).len(), 27);
+    }
+
+    #[test]
+    fn adjacent_empty_positions() {
+        let mut board = Board::new_with_size(2, 5);
+        assert!(board.adjacent_empty_positions().is_empty());
+
+        // the two pieces share the neighbors between them
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,2]).unwrap();
+        assert_eq!(board.adjacent_empty_positions(), vec![
+            vec![0,1], vec![0,3], vec![1,0], vec![1,1], vec![1,2], vec![1,3],
+        ]);
+    }
+
+    #[test]
+    fn adjacent_empty_positions_gravity() {
+        let mut board = Board::new_with_gravity(2, 1);
+        board.place_piece(Player::P1, &[1,0]).unwrap();
+
+        // [0,1] and [2,1] are next to the piece but have nothing under them
+        assert_eq!(board.adjacent_empty_positions(), vec![vec![0,0], vec![1,1], vec![2,0]]);
+    }
+
+    #[test]
+    fn win_length_4_on_5x5() {
+        let mut board = Board::new_with_win_length(2, 5, 4);
+
+        assert_eq!(board.win_length(), 4);
+        assert_eq!(board.place_piece(Player::P1, &[1,0]).unwrap(), GameResult::Ongoing);
+        assert_eq!(board.place_piece(Player::P1, &[2,1]).unwrap(), GameResult::Ongoing);
+        assert_eq!(board.place_piece(Player::P1, &[3,2]).unwrap(), GameResult::Ongoing);
+        assert_eq!(board.place_piece(Player::P1, &[4,3]).unwrap(), GameResult::Win(1));
+    }
+
+    #[test]
+    fn win_length_gap() {
+        let mut board = Board::new_with_win_length(2, 5, 4);
+
+        for pos in [[0,2],[1,2],[3,2]] {
+            board.place_piece(Player::P1, &pos).unwrap();
+        }
+        let actual = board.place_piece(Player::P1, &[4,2]).unwrap(); // 4 pieces but not in a row
+
+        assert_eq!(actual, GameResult::Ongoing);
+    }
+
+    #[test]
+    fn win_length_open_lines() {
+        let mut board = Board::new_with_win_length(1, 5, 3);
+
+        board.place_piece(Player::P2, &[2]).unwrap();
+
+        assert!(!board.can_still_win(1)); // every run of 3 covers the middle
+        assert_eq!(board.recommended_move(2), Some(vec![1]));
+    }
+
+    #[test]
+    fn reset() {
+        let mut board = Board::new(3).unwrap();
+
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,0,1]).unwrap();
+        board.place_piece(Player::P1, &[2,1,0]).unwrap();
+        board.reset();
+
+        assert!((0..board.data.len()).all(|i| board.get(&board.position_of(i)).unwrap().is_none()));
+    }
+
+    #[test]
+    fn draw_by_repetition() {
+        let start = Board::new(2).unwrap().zobrist_hash();
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        let one_piece = board.zobrist_hash();
+
+        // the position going back and forth between the empty board and a single piece
+        let mut history = Vec::new();
+        for (i, hash) in [start, one_piece].into_iter().cycle().take(8).enumerate() {
+            assert_eq!(Board::check_draw_by_repetition(&history, hash, 3), i >= 6, "after {i} positions");
+            history.push(hash);
+        }
+        assert!(!Board::check_draw_by_repetition(&history, 7, 1));
+        assert!(Board::check_draw_by_repetition(&history, 7, 0));
+    }
+
+    #[test]
+    fn game_draw_by_repetition() {
+        let mut game = Game::new(2, 2);
+        game.play(&[0,0]).unwrap();
+
+        // taking back and replaying the same move brings its position up again each time
+        for _ in 1..Game::DEFAULT_REPETITION_THRESHOLD {
+            assert_eq!(game.play(&[1,1]).unwrap(), GameResult::Ongoing);
+            game.undo_move().unwrap();
+        }
+        assert_eq!(game.play(&[1,1]).unwrap(), GameResult::Draw);
+        assert_eq!(game.state(), GameResult::Draw);
+        assert_eq!(game.play(&[2,2]), Err(PlaceError::GameOver.into()));
+
+        // a fresh game counts from scratch, and a higher threshold takes more cycles
+        game.reset();
+        game.set_repetition_threshold(4);
+        game.play(&[0,0]).unwrap();
+        for _ in 1..4 {
+            assert_eq!(game.play(&[1,1]).unwrap(), GameResult::Ongoing);
+            game.undo_move().unwrap();
+        }
+        assert_eq!(game.play(&[1,1]).unwrap(), GameResult::Draw);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 1 player")]
+    fn game_without_players() {
+        Game::new(2, 0);
+    }
+
+    #[test]
+    fn game_reset() {
+        let mut game = Game::new(2, 2);
+
+        game.play(&[0,0]).unwrap();
+        game.play(&[1,1]).unwrap();
+        game.play(&[2,2]).unwrap();
+        game.reset();
+
+        assert_eq!(game.current_player(), 1);
+        assert_eq!(game.move_count(), 0);
+        assert_eq!(game.state(), GameResult::Ongoing);
+        assert_eq!(game.board().get(&[1,1]).unwrap(), None);
+    }
+
+    #[test]
+    fn win_result_line() {
+        let mut board = Board::new(2).unwrap();
+        let expected = WinResult::Win { player: 2, line: vec![vec![0,2], vec![1,1], vec![2,0]] };
+
+        board.place_piece(Player::P2, &[0,2]).unwrap();
+        board.place_piece(Player::P2, &[2,0]).unwrap();
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+
+        let actual = board.is_win_at(&[1,1]).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn win_result_long_row() {
+        let mut board = Board::new_with_win_length(1, 6, 3);
+        let expected = WinResult::Win { player: 1, line: vec![vec![2], vec![3], vec![4]] };
+
+        for pos in [[1],[2],[3],[4],[5]] {
+            *board.get_mut(&pos).unwrap() = 1;
+        }
+
+        let actual = board.is_win_at(&[4]).unwrap();
+
+        assert_eq!(actual, expected);
+        assert_eq!(Board::new(2).unwrap().is_win_at(&[0,0]).unwrap(), WinResult::NoWin);
+    }
+
+    #[test]
+    fn compute_win_lines_count() {
+        // the classic 8 lines in 2d and 49 in 3d
+        assert_eq!(Board::new(2).unwrap().compute_win_lines().len(), 8);
+        assert_eq!(Board::new(3).unwrap().compute_win_lines().len(), 49);
+
+        // 3 in a row on a 4x4 board: 2 per row and column, 4 per diagonal direction
+        assert_eq!(Board::new_with_win_length(2, 4, 3).compute_win_lines().len(), 24);
+    }
+
+    #[test]
+    fn precomputed_win_matches() {
+        let mut board = Board::new(3).unwrap();
+        let mut cached = Board::new(3).unwrap();
+        cached.precompute_wins();
+
+        for pos in [[0,0,0], [1,1,0], [2,2,0], [2,0,0]] {
+            board.place_piece(Player::P1, &pos).unwrap();
+            cached.place_piece(Player::P1, &pos).unwrap();
+        }
+
+        for pos in [[0,0,0], [1,1,0], [2,2,0], [2,0,0], [0,2,0]] {
+            assert_eq!(cached.is_win_at(&pos).unwrap(), board.is_win_at(&pos).unwrap());
+        }
+        assert!(matches!(cached.is_win_at(&[1,1,0]).unwrap(), WinResult::Win { player: 1, .. }));
+    }
+
+    #[test]
+    fn zobrist_same_moves() {
+        let mut board = Board::new(3).unwrap();
+        let mut other = Board::new(3).unwrap();
+
+        // the same pieces in a different order should hash the same
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,1,0]).unwrap();
+        other.place_piece(Player::P2, &[1,1,0]).unwrap();
+        other.place_piece(Player::P1, &[0,0,0]).unwrap();
+
+        assert_eq!(board.zobrist_hash(), other.zobrist_hash());
+        assert_ne!(board.zobrist_hash(), 0);
+    }
+
+    #[test]
+    fn zobrist_one_piece_differs() {
+        let mut board = Board::new(3).unwrap();
+        let mut other = Board::new(3).unwrap();
+
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        other.place_piece(Player::P1, &[0,0,0]).unwrap();
+        other.place_piece(Player::P2, &[1,1,0]).unwrap();
+        assert_ne!(board.zobrist_hash(), other.zobrist_hash());
+
+        // undoing the extra piece brings the hashes back together
+        other.undo_piece_unchecked(&[1,1,0]).unwrap();
+        assert_eq!(board.zobrist_hash(), other.zobrist_hash());
+
+        // and a different player in the same cell hashes differently
+        other.undo_piece_unchecked(&[0,0,0]).unwrap();
+        other.place_piece(Player::P2, &[0,0,0]).unwrap();
+        assert_ne!(board.zobrist_hash(), other.zobrist_hash());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut board = Board::new_with_win_length(3, 4, 3);
+        board.set_gravity_axis(Some(2));
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P2, &[3,1,0]).unwrap();
+        board.place_piece(Player::P1, &[0,0,1]).unwrap();
+
+        let json = serde_json::to_string(&board).unwrap();
+        let actual: Board = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(actual.data, board.data);
+        assert_eq!(actual.sizes(), board.sizes());
+        assert_eq!(actual.win_length(), 3);
+        assert_eq!(actual.mode(), GameMode::Standard);
+        assert_eq!(actual.gravity_axis(), Some(2));
+        assert_eq!(actual.zobrist_hash(), board.zobrist_hash());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_gravity() {
+        let mut board = Board::new_with_gravity(2, 1);
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,1]).unwrap();
+
+        let json = serde_json::to_string(&board).unwrap();
+        let actual: Board = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(actual.drop_position(&[0]).unwrap(), vec![0,2]);
+        assert_eq!(actual.drop_position(&[1]).unwrap(), vec![1,0]);
+        assert_eq!(actual.validate_invariants(), Ok(()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_bad_data() {
+        let json = r#"{"dimension":2,"sizes":[3,3],"win_length":3,"data":[0,0,0]}"#;
+
+        assert!(serde_json::from_str::<Board>(json).is_err());
+    }
+
+    #[test]
+    fn positions_2d() {
+        let board = Board::new(2).unwrap();
+
+        let actual: Vec<_> = board.positions().collect();
+
+        assert_eq!(actual.len(), 9);
+        assert_eq!(actual[..4], [vec![0,0], vec![0,1], vec![0,2], vec![1,0]]);
+        assert_eq!(actual.last(), Some(&vec![2,2]));
+    }
+
+    #[test]
+    fn positions_rect() {
+        let board = Board::new_rect(&[2, 3, 1]);
+
+        let actual: Vec<_> = board.positions().collect();
+
+        assert_eq!(actual.len(), 6);
+        assert!(actual.iter().all(|pos| board.get(pos).is_ok()));
+    }
+
+    #[test]
+    fn occupied_and_empty_positions() {
+        let mut board = Board::new(3).unwrap();
+
+        for (player, pos) in [(1,[0,0,0]), (2,[1,1,0]), (1,[0,0,1]), (2,[2,1,0])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        assert_eq!(board.occupied_positions().count(), 4);
+        assert_eq!(board.empty_positions().count(), 23);
+        assert_eq!(board.positions_of_player(1).collect::<Vec<_>>(), [vec![0,0,0], vec![0,0,1]]);
+        assert_eq!(board.positions_of_player(2).count(), 2);
+        assert_eq!(board.positions_of_player(3).count(), 0);
+    }
+
+    #[test]
+    fn count_pieces_for_player() {
+        let mut board = Board::new(2).unwrap();
+
+        for (player, pos) in [(1,[0,0]), (2,[1,1]), (1,[2,0])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        assert_eq!(board.count_pieces_for_player(1), 2);
+        assert_eq!(board.count_pieces_for_player(2), 1);
+        assert_eq!(board.count_pieces_for_player(3), 0);
+    }
+
+    #[test]
+    fn count_empty_cells() {
+        let mut board = Board::new(2).unwrap();
+        assert_eq!(board.count_empty_cells(), 9);
+
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+        assert_eq!(board.count_empty_cells(), 8);
+    }
+
+    #[test]
+    fn turn_number() {
+        let mut board = Board::new(2).unwrap();
+        assert_eq!(board.turn_number(), 0);
+        assert_eq!(board.current_player_guess(2), 1);
+
+        for (player, pos) in [(1,[1,1]), (2,[0,0]), (1,[2,0])] {
+            assert_eq!(board.current_player_guess(2), player);
+            board.place_piece_number(player, &pos).unwrap();
+        }
+        assert_eq!(board.num_pieces_total(), 3);
+        assert_eq!(board.turn_number(), 3);
+        assert_eq!(board.current_player_guess(2), 2);
+        assert_eq!(board.current_player_guess(3), 1);
+    }
+
+    #[test]
+    fn position_new() {
+        assert_eq!(Position::new(vec![0,1,2], 3, 3).map(Vec::from), Ok(vec![0,1,2]));
+        assert_eq!(Position::new(vec![0,1], 3, 3), Err(IndexError::OutOfDimension));
+        assert_eq!(Position::new(vec![0,3,2], 3, 3), Err(IndexError::OutOfBounds));
+    }
+
+    #[test]
+    fn position_display() {
+        let pos = Position::new(vec![1,2,3], 3, 4).unwrap();
+
+        assert_eq!(pos.to_string(), "(1, 2, 3)");
+        assert_eq!(Position::new(vec![], 0, 3).unwrap().to_string(), "()");
+    }
+
+    #[test]
+    fn position_on_board() {
+        let mut board = Board::new(2).unwrap();
+        let pos = Position::new(vec![1,1], 2, 3).unwrap();
+
+        board.place_piece(Player::P2, &pos).unwrap();
+        assert!(board.get_mut(&pos).is_ok());
+
+        assert_eq!(board.get(&pos), Ok(Some(Player::P2)));
+    }
+
+    #[test]
+    fn neighbors_center() {
+        let board = Board::new(2).unwrap();
+
+        let mut actual = board.neighbors(&[1,1]);
+        actual.sort();
+
+        assert_eq!(actual.len(), 8);
+        assert!(!actual.contains(&vec![1,1]));
+        assert_eq!(actual[0], vec![0,0]);
+        assert_eq!(Board::new(3).unwrap().neighbors(&[1,1,1]).len(), 26);
+    }
+
+    #[test]
+    fn neighbors_corner() {
+        let board = Board::new(2).unwrap();
+
+        let mut actual = board.neighbors(&[0,0]);
+        actual.sort();
+
+        assert_eq!(actual, [vec![0,1], vec![1,0], vec![1,1]]);
+        assert!(board.neighbors(&[0,4]).is_empty());
+    }
+
+    #[test]
+    fn threats_two_in_a_row() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,0]).unwrap();
+        board.place_piece(Player::P2, &[2,2]).unwrap();
+
+        let actual = board.threats_for_player(1);
+
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].empty_pos, vec![2,0]);
+        assert_eq!(actual[0].line, [vec![0,0], vec![1,0], vec![2,0]]);
+        assert!(board.threats_for_player(2).is_empty());
+    }
+
+    #[test]
+    fn threats_fork() {
+        let mut board = Board::new(2).unwrap();
+        board.precompute_wins();
+        for pos in [[0,0], [2,0], [1,1], [1,2]] {
+            board.place_piece(Player::P1, &pos).unwrap();
+        }
+
+        let threats = board.threats_for_player(1);
+        let at_fork = threats.iter().filter(|threat| threat.empty_pos == [1,0]).count();
+
+        assert_eq!(threats.len(), 4);
+        assert_eq!(at_fork, 2);
+
+        // a threat that needs a piece under it first isn't one yet
+        let mut board = Board::new_with_gravity(3, 2);
+        board.place_piece(Player::P2, &[1,0,0]).unwrap();
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,0,1]).unwrap();
+        assert!(board.threats_for_player(1).is_empty());
+        board.set_gravity_axis(None);
+        assert_eq!(board.threats_for_player(1).len(), 1);
+    }
+
+    #[test]
+    fn immediate_winning_moves() {
+        let mut board = Board::new(2).unwrap();
+        for (player, pos) in [(1,[0,0]), (1,[1,1]), (1,[2,0]), (2,[2,2])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+        let expected = [vec![1,0], vec![0,2]];
+
+        let mut actual = board.immediate_winning_moves(1);
+        actual.sort_by_key(|pos| pos[1]);
+
+        assert_eq!(actual, expected);
+        assert!(board.immediate_winning_moves(2).is_empty());
+        assert_eq!(board.get(&[1,0]), Ok(None)); // the board is left as it was
+    }
+
+    #[test]
+    fn blocking_moves() {
+        let mut board = Board::new(2).unwrap();
+        for (player, pos) in [(2,[0,1]), (2,[1,1]), (2,[2,0]), (1,[0,0])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        let mut actual = board.blocking_moves(1, 2);
+        actual.sort();
+
+        assert_eq!(actual, [vec![0,2], vec![2,1]]);
+        assert!(board.blocking_moves(2, 1).is_empty());
+    }
+
+    #[test]
+    fn misere_board_loss() {
+        let mut board = Board::new(2).unwrap();
+        board.set_mode(GameMode::Misere);
+        let expected = GameResult::Loss(2);
+
+        board.place_piece(Player::P2, &[0,2]).unwrap();
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+        let actual = board.place_piece(Player::P2, &[2,0]).unwrap();
 
         assert_eq!(actual, expected);
+        assert_eq!(board.mode(), GameMode::Misere);
+        assert_eq!(board.clone().mode(), GameMode::Misere);
+    }
+
+    #[test]
+    fn misere_game_draw() {
+        let mut game = Game::new_misere(2, 2);
+
+        // X O X / X O O / O X X has no line for anyone
+        let moves = [[0,0], [1,0], [2,0], [1,1], [0,1], [2,1], [1,2], [0,2], [2,2]];
+        let states: Vec<_> = moves.iter().map(|pos| game.play(pos).unwrap()).collect();
+
+        assert!(states[..8].iter().all(|state| *state == GameResult::Ongoing));
+        assert_eq!(states[8], GameResult::Draw);
+        assert_eq!(game.mode(), GameMode::Misere);
+    }
+
+    #[test]
+    fn gravity_last_axis() {
+        let mut board = Board::new_with_gravity(3, 2);
+
+        assert_eq!(board.place_piece(Player::P1, &[2,1,1]), Err(PlaceError::Unsupported.into()));
+        board.place_piece(Player::P1, &[2,1,0]).unwrap();
+        board.place_piece(Player::P2, &[2,1,1]).unwrap();
+
+        // pieces along the other axes don't need support
+        board.place_piece(Player::P1, &[0,2,0]).unwrap();
+        assert_eq!(board.gravity_axis(), Some(2));
+    }
+
+    #[test]
+    fn gravity_middle_axis() {
+        let mut board = Board::new_with_gravity(3, 1);
+
+        assert_eq!(board.place_piece(Player::P1, &[0,1,0]), Err(PlaceError::Unsupported.into()));
+        board.place_piece(Player::P1, &[0,0,2]).unwrap();
+        board.place_piece(Player::P2, &[0,1,2]).unwrap();
+        board.place_piece(Player::P1, &[2,0,1]).unwrap();
+
+        assert!(board.can_place_at(&[0,2,2]));
+        assert!(!board.can_place_at(&[1,1,1]));
+        assert_eq!(board.legal_moves(1).len(), 9 - 2 + 2);
+    }
+
+    #[test]
+    fn no_gravity_by_default() {
+        let mut board = Board::new(3).unwrap();
+
+        assert_eq!(board.gravity_axis(), None);
+        assert!(board.place_piece(Player::P1, &[1,1,2]).is_ok());
+    }
+
+    #[test]
+    fn game_with_gravity() {
+        let mut game = Game::with_board(Board::new_with_gravity(3, 2), 2);
+
+        assert_eq!(game.play(&[1,1,1]), Err(PlaceError::Unsupported.into()));
+        assert_eq!(game.current_player(), 1);
+        assert_eq!(game.play(&[1,1,0]), Ok(GameResult::Ongoing));
+        assert_eq!(game.play(&[1,1,1]), Ok(GameResult::Ongoing));
+    }
+
+    #[test]
+    fn drop_position_empty_column() {
+        let board = Board::new_with_gravity(3, 2);
+
+        assert_eq!(board.drop_position(&[1,2]), Ok(vec![1,2,0]));
+        assert_eq!(board.drop_position(&[1,4]), Err(IndexError::OutOfBounds.into()));
+        assert_eq!(board.drop_position(&[1]), Err(IndexError::OutOfDimension.into()));
+        assert_eq!(Board::new(3).unwrap().drop_position(&[1,2]), Err(IndexError::OutOfDimension.into()));
+    }
+
+    #[test]
+    fn drop_position_partly_filled() {
+        let mut board = Board::new_with_gravity(3, 0);
+
+        board.place_piece(Player::P1, &[0,2,1]).unwrap();
+        assert_eq!(board.drop_position(&[2,1]), Ok(vec![1,2,1]));
+
+        board.place_piece(Player::P2, &[1,2,1]).unwrap();
+        assert_eq!(board.drop_position(&[2,1]), Ok(vec![2,2,1]));
+    }
+
+    #[test]
+    fn drop_position_full_column() {
+        let mut board = Board::new_with_gravity(2, 1);
+
+        for player in [1, 2, 1] {
+            let pos = board.drop_position(&[0]).unwrap();
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        assert_eq!(board.drop_position(&[0]), Err(PlaceError::Unsupported.into()));
+    }
+
+    #[test]
+    fn gravity_column_is_full() {
+        let mut board = Board::new_with_gravity(3, 1);
+        assert_eq!(board.gravity_column_is_full(&[0,2]), Ok(false));
+
+        board.place_piece(Player::P1, &[0,0,2]).unwrap();
+        board.place_piece(Player::P2, &[0,1,2]).unwrap();
+        assert_eq!(board.gravity_column_is_full(&[0,2]), Ok(false));
+
+        board.place_piece(Player::P1, &[0,2,2]).unwrap();
+        assert_eq!(board.gravity_column_is_full(&[0,2]), Ok(true));
+        assert_eq!(board.gravity_column_is_full(&[2,0]), Ok(false));
+
+        assert_eq!(board.gravity_column_is_full(&[0,3]), Err(IndexError::OutOfBounds));
+        assert_eq!(board.gravity_column_is_full(&[0]), Err(IndexError::OutOfDimension));
+        assert_eq!(Board::new(2).unwrap().gravity_column_is_full(&[0]), Err(IndexError::OutOfDimension));
+    }
+
+    #[test]
+    fn column_heights_consistent() {
+        let mut board = Board::new_with_gravity(3, 1);
+        let moves = [[0,0,0], [0,1,0], [2,0,2], [0,2,0], [2,1,2], [1,0,1]];
+
+        let check = |board: &Board| {
+            let mut recomputed = board.clone();
+            recomputed.recompute_column_heights();
+            assert_eq!(board.column_heights, recomputed.column_heights);
+        };
+
+        for pos in &moves {
+            board.place_piece(Player::P1, pos).unwrap();
+            check(&board);
+        }
+        assert_eq!(board.column_heights.get(&vec![0,0]), Some(&3));
+        assert_eq!(board.column_heights.get(&vec![2,2]), Some(&2));
+
+        for pos in moves.iter().rev() {
+            board.undo_piece_unchecked(pos).unwrap();
+            check(&board);
+        }
+        assert!(board.column_heights.is_empty());
+    }
+
+    #[test]
+    fn column_heights_game_undo() {
+        let mut game = Game::with_board(Board::new_with_gravity(2, 1), 2);
+
+        game.play(&[1,0]).unwrap();
+        game.play(&[1,1]).unwrap();
+        assert_eq!(game.board().drop_position(&[1]), Ok(vec![1,2]));
+
+        game.undo_move().unwrap();
+        assert_eq!(game.board().drop_position(&[1]), Ok(vec![1,1]));
+
+        game.reset();
+        assert_eq!(game.board().drop_position(&[1]), Ok(vec![1,0]));
+    }
+
+    #[test]
+    fn all_lines_2d() {
+        let board = Board::new(2).unwrap();
+
+        let lines: Vec<_> = board.all_lines().collect();
+
+        assert_eq!(lines.len(), 8);
+        assert!(lines.contains(&Line {
+            positions: vec![vec![0,2], vec![1,1], vec![2,0]],
+            direction: vec![1,-1],
+        }));
+        assert!(lines.iter().all(|line| line.positions.len() == 3));
+    }
+
+    #[test]
+    fn all_lines_short_win() {
+        let board = Board::new_with_win_length(3, 4, 2);
+
+        // every pair of neighbors is a line
+        let expected = board.positions()
+            .map(|pos| board.neighbors(&pos).len())
+            .sum::<usize>() / 2;
+
+        assert_eq!(board.all_lines().count(), expected);
+    }
+
+    #[test]
+    fn lines_through_position() {
+        let board = Board::new(2).unwrap();
+        assert_eq!(board.lines_through_position(&[1,1]).len(), 4);
+        assert_eq!(board.lines_through_position(&[0,0]).len(), 3);
+        assert_eq!(board.lines_through_position(&[1,0]).len(), 2);
+
+        let board = Board::new(3).unwrap();
+        assert_eq!(board.lines_through_position(&[1,1,1]).len(), 13);
+        assert_eq!(board.lines_through_position(&[0,0,0]).len(), 7);
+        assert!(board.lines_through_position(&[0,0,0]).iter().all(|line| line.positions.contains(&vec![0,0,0])));
+    }
+
+    #[test]
+    fn for_each_line_through() {
+        let board = Board::new(3).unwrap();
+
+        for pos in [[1,1,1], [0,0,0], [0,1,2]] {
+            let mut calls = 0;
+            board.for_each_line_through(&pos, |line| {
+                assert!(line.iter().any(|other| other.as_slice() == pos));
+                calls += 1;
+                ControlFlow::Continue(())
+            });
+            assert_eq!(calls, board.lines_through_position(&pos).len());
+        }
+
+        let mut calls = 0;
+        board.for_each_line_through(&[1,1,1], |_| {
+            calls += 1;
+            if calls == 2 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        });
+        assert_eq!(calls, 2);
+
+        board.for_each_line_through(&[3,0,0], |_| panic!("no lines off the board"));
+    }
+
+    #[test]
+    fn for_each_line_through_short_win() {
+        // a row of 4 has 2 lines of 3 through its second cell
+        let board = Board::new_with_win_length(1, 4, 3);
+        let mut lines = Vec::new();
+        board.for_each_line_through(&[1], |line| {
+            lines.push(line.to_vec());
+            ControlFlow::Continue(())
+        });
+        assert_eq!(lines, vec![vec![vec![0], vec![1], vec![2]], vec![vec![1], vec![2], vec![3]]]);
+    }
+
+    #[test]
+    fn line_score() {
+        let mut board = Board::new(2).unwrap();
+        let row = |y| Line { positions: vec![vec![0,y], vec![1,y], vec![2,y]], direction: vec![1,0] };
+
+        for (player, pos) in [(1,[0,0]), (1,[1,0]), (1,[2,0]), (1,[0,1]), (2,[1,1]), (2,[0,2]), (2,[1,2])] {
+            *board.get_mut(&pos).unwrap() = player;
+        }
+
+        assert_eq!(board.line_score(&row(0), 1), 10);
+        assert_eq!(board.line_score(&row(0), 2), -3);
+        assert_eq!(board.line_score(&row(1), 1), 0); // mixed
+        assert_eq!(board.line_score(&row(2), 1), -2);
+        assert_eq!(board.line_score(&row(2), 2), 2);
+        assert_eq!(Board::new(2).unwrap().line_score(&row(0), 1), 0);
+    }
+
+    #[test]
+    fn board_score() {
+        let mut board = Board::new(2).unwrap();
+        assert_eq!(board.board_score(1), 0);
+
+        // the center is on 4 lines, a corner on 3
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+        assert_eq!(board.board_score(1), 4);
+        assert_eq!(board.board_score(2), -4);
+
+        // the corner shares a diagonal with the center, which becomes mixed
+        board.place_piece(Player::P2, &[0,0]).unwrap();
+        assert_eq!(board.board_score(1), 3 - 2);
+    }
+
+    #[test]
+    fn from_2d_array() {
+        let board = Board::from_2d_array([
+            [1, 2, 0],
+            [0, 1, 2],
+            [0, 0, 1],
+        ]);
+        assert_eq!(board.get(&[1,0]), Ok(Some(Player::P2)));
+        assert_eq!(board.get(&[0,1]), Ok(None));
+        assert_eq!(board.winning_player(), Some(Player::P1));
+        assert_eq!(board.validate_invariants(), Ok(()));
+
+        let board = Board::from_2d_array([
+            [1, 2, 1],
+            [1, 2, 0],
+            [0, 2, 0],
+        ]);
+        assert_eq!(board.winning_player(), Some(Player::P2));
+        assert_eq!(Board::from_2d_array([[1, 2, 1]; 3]).winning_player(), Some(Player::P1));
+        assert_eq!(Board::from_2d_array([[0; 3]; 3]).winning_player(), None);
     }
 
     #[test]
-    fn win_straight() {
-        let mut board = Board::new(2);
-        let expected = true;
+    fn from_flat_slice() {
+        let mut data = vec![0; 27];
+        data[13] = 2;
+        let board = Board::from_flat_slice(3, 3, &data).unwrap();
+        assert_eq!(board.get(&[1,1,1]), Ok(Some(Player::P2)));
+        assert_eq!(board.zobrist_hash(), {
+            let mut placed = Board::new(3).unwrap();
+            placed.place_piece(Player::P2, &[1,1,1]).unwrap();
+            placed.zobrist_hash()
+        });
 
-        board.place_piece(1, &[0,0]).unwrap();
-        board.place_piece(1, &[0,1]).unwrap();
-        let actual = board.place_piece(1, &[0,2]).unwrap();
+        assert_eq!(Board::from_flat_slice(3, 3, &data[1..]).unwrap_err(), IndexError::OutOfBounds);
+        assert_eq!(Board::from_flat_slice(0, 3, &[]).unwrap_err(), IndexError::OutOfDimension);
+        assert_eq!(Board::from_flat_slice(2, 0, &[]).unwrap_err(), IndexError::OutOfDimension);
+    }
 
-        assert_eq!(actual, expected);
+    #[test]
+    fn winning_player() {
+        let mut board = Board::new(3).unwrap();
+        board.place_piece(Player::P2, &[0,1,2]).unwrap();
+        board.place_piece(Player::P1, &[1,1,1]).unwrap();
+        assert_eq!(board.winning_player(), None);
+
+        // a diagonal through the middle, saved and loaded with no record of the last move
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P1, &[2,2,2]).unwrap();
+        let loaded = Board::from_bytes(&board.to_bytes()).unwrap();
+        assert_eq!(loaded.winning_player(), Some(Player::P1));
     }
 
     #[test]
-    fn win_diag() {
-        let mut board = Board::new(2);
-        let expected = true;
+    fn all_winning_lines_for_player() {
+        let board = Board::from_2d_array([
+            [1, 2, 0],
+            [1, 2, 0],
+            [0, 0, 1],
+        ]);
+        assert!(board.all_winning_lines_for_player(1).is_empty());
 
-        board.place_piece(1, &[0,0]).unwrap();
-        board.place_piece(1, &[1,1]).unwrap();
-        let actual = board.place_piece(1, &[2,2]).unwrap();
+        // the corner completes the bottom row and the diagonal at once
+        let board = Board::from_2d_array([
+            [0, 2, 1],
+            [2, 1, 2],
+            [1, 1, 1],
+        ]);
+        let lines = board.all_winning_lines_for_player(1);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.positions.contains(&vec![0,2])));
+        assert!(board.all_winning_lines_for_player(2).is_empty());
+    }
 
-        assert_eq!(actual, expected);
+    #[test]
+    fn distance_to_win() {
+        let mut board = Board::new(2).unwrap();
+        assert_eq!(board.distance_to_win(1), Some(3));
+
+        for (player, pos) in [(1,[0,0]), (2,[1,0]), (1,[1,1]), (2,[2,2])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+        assert_eq!(board.distance_to_win(1), Some(2));
+        assert_eq!(board.distance_to_win(2), Some(2));
+
+        // a fork down the left column and the other diagonal, a forced win
+        board.place_piece(Player::P1, &[0,2]).unwrap();
+        assert_eq!(board.distance_to_win(1), Some(1));
+        assert_eq!(board.threat_count_for_player(1), 2);
+
+        board.place_piece(Player::P1, &[0,1]).unwrap();
+        assert_eq!(board.distance_to_win(1), Some(0));
+    }
+
+    #[test]
+    fn distance_to_win_blocked() {
+        let board = Board::from_2d_array([
+            [1, 2, 1],
+            [1, 2, 2],
+            [2, 1, 1],
+        ]);
+        assert_eq!(board.distance_to_win(1), None);
+        assert_eq!(board.distance_to_win(2), None);
+    }
+
+    #[test]
+    fn is_draw_forced() {
+        // a cell left, but every row, column and diagonal already has both players in it
+        let board = Board::from_2d_array([
+            [1, 2, 1],
+            [1, 2, 0],
+            [2, 1, 2],
+        ]);
+        assert!(board.is_draw_forced());
+        assert!(!board.is_full());
+
+        // the right column is still open for player 1
+        let board = Board::from_2d_array([
+            [1, 2, 1],
+            [2, 2, 0],
+            [0, 1, 0],
+        ]);
+        assert!(!board.is_draw_forced());
+        assert!(!Board::new(2).unwrap().is_draw_forced());
+    }
+
+    #[test]
+    fn score_player_lines() {
+        let weights = Board::default_weights(3);
+        assert_eq!(weights, vec![0, 1, 10, 100]);
+
+        let mut board = Board::new(2).unwrap();
+        assert_eq!(board.score_player_lines(1, &weights), 0);
+
+        // the corner is on a row, a column and a diagonal
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        assert_eq!(board.score_player_lines(1, &weights), 3);
+
+        // an opponent's piece on the diagonal takes it away
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+        assert_eq!(board.score_player_lines(1, &weights), 2);
+        assert_eq!(board.score_player_lines(2, &weights), 4 - 1);
+    }
+
+    #[test]
+    fn score_player_lines_full_line() {
+        let weights = Board::default_weights(3);
+        let mut board = Board::new(1).unwrap();
+
+        for pos in [[0], [1], [2]] {
+            board.place_piece(Player::P1, &pos).unwrap();
+        }
+        assert_eq!(board.score_player_lines(1, &weights), weights[3]);
+        assert_eq!(board.score_player_lines(2, &weights), 0);
+    }
+
+    #[test]
+    fn two_way_threats() {
+        // X threatens the top row and the right column, which meet at [2,0]
+        let board = Board::from_2d_array([
+            [1, 1, 0],
+            [2, 2, 1],
+            [2, 0, 1],
+        ]);
+        assert_eq!(board.two_way_threats(1), vec![vec![2,0]]);
+        assert_eq!(board.threats_for_player(1).len(), 2);
+
+        // two threats apart from each other can't both be blocked
+        let board = Board::from_2d_array([
+            [1, 1, 0],
+            [2, 2, 0],
+            [0, 0, 0],
+        ]);
+        assert!(board.two_way_threats(1).is_empty());
+        assert!(Board::new(2).unwrap().two_way_threats(1).is_empty());
+    }
+
+    #[test]
+    fn threat_count_for_player() {
+        let mut board = Board::new(2).unwrap();
+        assert_eq!(board.threat_count_for_player(1), 0);
+
+        for (player, pos) in [(1,[0,0]), (2,[2,2]), (1,[1,0]), (2,[2,1])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+        assert_eq!(board.threat_count_for_player(1), board.threats_for_player(1).len());
+        assert_eq!(board.threat_count_for_player(1), 1);
+
+        board.place_piece(Player::P1, &[0,1]).unwrap();
+        assert_eq!(board.threat_count_for_player(1), board.threats_for_player(1).len());
+        // the bottom row and the left column, with player 2 in the way on the diagonal
+        assert_eq!(board.threat_count_for_player(1), 2);
+    }
+
+    #[test]
+    fn total_eval_score_orders_threats() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        let quiet = board.total_eval_score(1, 2);
+
+        board.place_piece(Player::P1, &[1,0]).unwrap();
+        let one_threat = board.total_eval_score(1, 2);
+
+        board.place_piece(Player::P1, &[0,1]).unwrap();
+        let fork = board.total_eval_score(1, 2);
+
+        assert!(quiet < one_threat && one_threat < fork);
+        assert!(board.total_eval_score(2, 1) < 0);
+
+        board.place_piece(Player::P1, &[2,0]).unwrap();
+        assert!(board.total_eval_score(1, 2) > 1000);
+    }
+
+    #[test]
+    fn any_winner() {
+        let mut board = Board::new(2).unwrap();
+        assert_eq!(board.any_winner(), None);
+
+        for (player, pos) in [(1,[0,0]), (2,[1,0]), (1,[1,1]), (2,[2,0])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+        assert_eq!(board.any_winner(), None);
+        assert_eq!(board.count_lines_won_by(1), 0);
+
+        board.place_piece(Player::P1, &[2,2]).unwrap();
+        assert_eq!(board.any_winner(), Some(1));
+        assert_eq!(board.count_lines_won_by(1), 1);
+        assert_eq!(board.count_lines_won_by(2), 0);
+
+        // filling the bottom row and left column at once
+        let mut board = Board::new(2).unwrap();
+        for pos in [[0,0], [1,0], [2,0], [0,1], [0,2]] {
+            *board.get_mut(&pos).unwrap() = 3;
+        }
+        assert_eq!(board.count_lines_won_by(3), 2);
+        assert_eq!(board.any_winner(), Some(3));
+    }
+
+    #[test]
+    fn num_winning_lines_for_player() {
+        let mut board = Board::new(3).unwrap();
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P1, &[1,1,1]).unwrap();
+        assert_eq!(board.num_winning_lines_for_player(1), 0);
+
+        board.place_piece(Player::P1, &[2,2,2]).unwrap();
+        assert_eq!(board.num_winning_lines_for_player(1), 1);
+        assert_eq!(board.num_winning_lines_for_player(2), 0);
+
+        // the center of the bottom layer completes both of its diagonals at once
+        for pos in [[2,0,0], [0,2,0], [2,2,0], [1,1,0]] {
+            board.place_piece(Player::P1, &pos).unwrap();
+        }
+        assert_eq!(board.num_winning_lines_for_player(1), 3);
+    }
+
+    #[test]
+    fn can_win_in_one() {
+        let mut board = Board::new(2).unwrap();
+        assert_eq!(board.can_win_in_one(1), None);
+
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+        board.place_piece(Player::P1, &[1,1]).unwrap();
+        assert_eq!(board.can_win_in_one(1), Some(vec![2,2]));
+        assert_eq!(board.can_win_in_one(2), None);
+
+        board.place_piece(Player::P2, &[2,2]).unwrap();
+        assert_eq!(board.can_win_in_one(1), None);
+    }
+
+    #[test]
+    fn can_win_in_one_matches_winning_moves() {
+        let mut board = Board::new_with_gravity(3, 2);
+        for (player, pos) in [(1,[0,0,0]), (2,[1,0,0]), (1,[0,1,0]), (2,[1,1,0]), (1,[2,2,0])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        for player in [1, 2] {
+            let expected = board.immediate_winning_moves(player);
+            let actual = board.can_win_in_one(player);
+
+            assert_eq!(actual.is_some(), !expected.is_empty());
+            assert!(actual.is_none_or(|pos| expected.contains(&pos)));
+        }
+    }
+
+    #[test]
+    fn fork_positions_center() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P1, &[2,0]).unwrap();
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+
+        // the center makes threats on both diagonals
+        let actual = board.fork_positions(1);
+
+        assert!(actual.contains(&vec![1,1]));
+        assert!(!actual.contains(&vec![0,1])); // only the column threat
+        assert!(board.fork_positions(2).is_empty());
+    }
+
+    #[test]
+    fn fork_positions_opposite_corners() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,1]).unwrap();
+        board.place_piece(Player::P1, &[2,2]).unwrap();
+
+        // either free corner makes threats along both of its sides
+        let mut actual = board.fork_positions(1);
+        actual.sort();
+
+        assert_eq!(actual, [vec![0,2], vec![2,0]]);
+    }
+
+    #[test]
+    fn mirror_position() {
+        let mut board = Board::new(3).unwrap();
+        board.place_piece(Player::P1, &[0,1,2]).unwrap();
+
+        let mirrored = board.mirror(0);
+
+        assert_eq!(mirrored.get(&[2,1,2]), Ok(Some(Player::P1)));
+        assert_eq!(mirrored.get(&[0,1,2]), Ok(None));
+        assert_eq!(mirrored.count_pieces_for_player(1), 1);
+    }
+
+    #[test]
+    fn mirror_twice() {
+        let mut board = Board::new_rect(&[3, 4]);
+        for (player, pos) in [(1,[0,0]), (2,[1,3]), (1,[2,1])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        for axis in 0..2 {
+            let actual = board.mirror(axis).mirror(axis);
+
+            assert_eq!(actual.data, board.data);
+            assert_eq!(actual.zobrist_hash(), board.zobrist_hash());
+        }
+    }
+
+    #[test]
+    fn mirror_keeps_score() {
+        let mut board = Board::new(3).unwrap();
+        for (player, pos) in [(1,[0,0,0]), (2,[1,1,0]), (1,[2,1,0]), (2,[0,2,1])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        for axis in 0..3 {
+            let mirrored = board.mirror(axis);
+
+            assert_ne!(mirrored.data, board.data);
+            for player in [1, 2] {
+                assert_eq!(mirrored.board_score(player), board.board_score(player));
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_quarter_turn() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+
+        let rotated = board.rotate(0, 1);
+
+        assert_eq!(rotated.get(&[2,0]), Ok(Some(Player::P1)));
+        assert_eq!(rotated.get(&[2,1]), Ok(Some(Player::P2)));
+    }
+
+    #[test]
+    fn rotate_four_times() {
+        let mut board = Board::new(3).unwrap();
+        for (player, pos) in [(1,[0,0,0]), (2,[1,2,0]), (1,[2,1,2])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+
+        for (a, b) in [(0,1), (1,2), (2,0)] {
+            let actual = board.rotate(a, b).rotate(a, b).rotate(a, b).rotate(a, b);
+
+            assert_ne!(board.rotate(a, b).data, board.data);
+            assert_eq!(actual.data, board.data);
+            assert_eq!(actual.zobrist_hash(), board.zobrist_hash());
+        }
+    }
+
+    #[test]
+    fn rotate_keeps_wins() {
+        let mut board = Board::new(3).unwrap();
+        for pos in [[0,1,0], [1,1,1], [2,1,2]] {
+            board.place_piece(Player::P1, &pos).unwrap();
+        }
+
+        for (a, b) in [(0,1), (0,2), (1,2)] {
+            let rotated = board.rotate(a, b);
+
+            let won = rotated.occupied_positions()
+                .all(|pos| matches!(rotated.is_win_at(&pos), Ok(WinResult::Win { player: 1, .. })));
+            assert!(won);
+            assert_eq!(rotated.board_score(1), board.board_score(1));
+        }
+    }
+
+    #[test]
+    fn symmetries_count() {
+        assert_eq!(Board::new(2).unwrap().symmetries().len(), 8);
+        assert_eq!(Board::new(3).unwrap().symmetries().len(), 48);
+        assert_eq!(Board::new_rect(&[3, 4]).symmetries().len(), 4);
+        assert_eq!(Board::new_with_gravity(3, 2).symmetries().len(), 8);
+    }
+
+    #[test]
+    fn canonical_form_of_rotations() {
+        let mut board = Board::new(2).unwrap();
+        board.place_piece(Player::P1, &[0,0]).unwrap();
+        board.place_piece(Player::P2, &[1,0]).unwrap();
+        board.place_piece(Player::P1, &[2,1]).unwrap();
+        let rotated = board.rotate(0, 1);
+
+        let expected = board.to_canonical_form();
+        let actual = rotated.to_canonical_form();
+
+        assert_ne!(rotated.data, board.data);
+        assert_eq!(actual.data, expected.data);
+        assert_eq!(board.mirror(1).to_canonical_form().data, expected.data);
+    }
+
+    #[test]
+    fn canonical_form_differs() {
+        let mut corner = Board::new(2).unwrap();
+        corner.place_piece(Player::P1, &[0,0]).unwrap();
+        let mut center = Board::new(2).unwrap();
+        center.place_piece(Player::P1, &[1,1]).unwrap();
+
+        assert_ne!(corner.to_canonical_form().data, center.to_canonical_form().data);
+    }
+
+    #[test]
+    fn symmetry_class_size() {
+        for dimension in 1..=4 {
+            let mut board = Board::new(dimension).unwrap();
+            assert_eq!(board.symmetry_class_size(), 1);
+
+            board.place_piece(Player::P1, &vec![0; usize::from(dimension)]).unwrap();
+            assert_eq!(board.symmetry_class_size(), 1 << dimension);
+        }
+
+        let mut center = Board::new(3).unwrap();
+        center.place_piece(Player::P1, &[1,1,1]).unwrap();
+        assert_eq!(center.symmetry_class_size(), 1);
+    }
+
+    #[test]
+    fn game_stats() {
+        let mut stats = GameStats::new();
+        assert_eq!(stats.win_rate(1), 0.0);
+        assert_eq!(stats.avg_moves(), 0.0);
+
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..100 {
+            let mut game = Game::new(2, 2);
+            let result = loop {
+                // xorshift, enough to play a different game each time
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+
+                let moves = game.board().legal_moves(game.current_player());
+                let pos = &moves[(seed % moves.len() as u64) as usize];
+                match game.play(pos).unwrap() {
+                    GameResult::Ongoing => {},
+                    result => break result,
+                }
+            };
+            stats.record_game(result, game.move_count());
+        }
+
+        assert_eq!(stats.num_games, 100);
+        assert_eq!(stats.draws + stats.wins_per_player.values().sum::<u32>(), stats.num_games);
+        assert!(stats.win_rate(1) + stats.win_rate(2) <= 1.0);
+        assert!(stats.win_rate(1) > 0.0 && stats.win_rate(3) == 0.0);
+        assert!((5.0..=9.0).contains(&stats.avg_moves()));
+    }
+
+    #[test]
+    fn game_stats_record() {
+        let mut stats = GameStats::new();
+        stats.record_game(GameResult::Win(2), 7);
+        stats.record_game(GameResult::Draw, 9);
+        stats.record_game(GameResult::Win(2), 5);
+        stats.record_game(GameResult::Loss(1), 3);
+
+        assert_eq!(stats.wins_per_player, HashMap::from([(2, 2)]));
+        assert_eq!(stats.draws, 1);
+        assert_eq!(stats.win_rate(2), 0.5);
+        assert_eq!(stats.avg_moves(), 6.0);
+    }
+
+    #[test]
+    fn compact_notation() {
+        let mut board = Board::new(3).unwrap();
+        for (player, pos) in [(1,[0,0,0]), (2,[1,1,1]), (1,[2,2,2])] {
+            board.place_piece_number(player, &pos).unwrap();
+        }
+        assert_eq!(board.compact_notation(), "1@(0,0,0) 2@(1,1,1) 1@(2,2,2)");
+
+        assert_eq!(Board::new(2).unwrap().compact_notation(), "");
+    }
+
+    #[test]
+    fn compact_notation_round_trip() {
+        let mut big = Board::new_with_size(4, 5);
+        for (player, pos) in [(3,[4,0,2,1]), (1,[0,0,0,0]), (7,[1,4,4,3])] {
+            *big.get_mut(&pos).unwrap() = player;
+        }
+        let mut line = Board::new_with_size(1, 9);
+        *line.get_mut(&[8]).unwrap() = 2;
+
+        for board in [big, line, Board::new(2).unwrap()] {
+            let notation = board.compact_notation();
+            let parsed = Board::from_compact_notation(&notation, board.dimension, board.size()).unwrap();
+
+            assert_eq!(parsed.data, board.data);
+            assert_eq!(parsed.zobrist_hash(), Board::from_flat_slice(board.dimension, board.size(), &board.data).unwrap().zobrist_hash());
+        }
+    }
+
+    #[test]
+    fn from_compact_notation_errors() {
+        let parse = |s| Board::from_compact_notation(s, 2, 3).map(|board| board.data);
+
+        assert_eq!(parse("  1@(0,0)   2@(1,0) "), Ok(vec![1,2,0, 0,0,0, 0,0,0]));
+        assert_eq!(parse("1@(0,3)"), Err(ParseError::IndexError(IndexError::OutOfBounds)));
+        assert_eq!(parse("1@(0,0,0)"), Err(ParseError::IndexError(IndexError::OutOfDimension)));
+        assert_eq!(parse("1@(0,0) 2@(0,0)"), Err(ParseError::InvalidFormat));
+        assert_eq!(parse("0@(0,0)"), Err(ParseError::InvalidFormat));
+        assert_eq!(parse("1(0,0)"), Err(ParseError::InvalidFormat));
+        assert_eq!(parse("1@0,0"), Err(ParseError::InvalidFormat));
+        assert!(matches!(parse("x@(0,0)"), Err(ParseError::InvalidNumber(_))));
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut board = Board::new_with_gravity(3, 2);
+        board.set_mode(GameMode::Misere);
+        board.place_piece(Player::P1, &[0,0,0]).unwrap();
+        board.place_piece(Player::P2, &[0,0,1]).unwrap();
+
+        let bytes = board.to_bytes();
+        let actual = Board::from_bytes(&bytes).unwrap();
+
+        assert_eq!(bytes[..9], [3, 0, 3, 3, 3, 3, 1, 3, 0]);
+        assert_eq!(actual.data, board.data);
+        assert_eq!(actual.sizes(), board.sizes());
+        assert_eq!(actual.mode(), GameMode::Misere);
+        assert_eq!(actual.gravity_axis(), Some(2));
+        assert_eq!(actual.zobrist_hash(), board.zobrist_hash());
+        assert_eq!(actual.drop_position(&[0,0]), Ok(vec![0,0,2]));
+
+        let rect = Board::new_with_win_length(2, 4, 2);
+        assert_eq!(Board::from_bytes(&rect.to_bytes()).unwrap().to_bytes(), rect.to_bytes());
+    }
+
+    #[test]
+    fn bytes_truncated() {
+        let bytes = Board::new(2).unwrap().to_bytes();
+
+        assert_eq!(Board::from_bytes(&[]).unwrap_err(), DeserializeError::Truncated);
+        assert_eq!(Board::from_bytes(&bytes[..2]).unwrap_err(), DeserializeError::Truncated);
+        assert_eq!(Board::from_bytes(&bytes[..5]).unwrap_err(), DeserializeError::Truncated);
+        assert_eq!(Board::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(), DeserializeError::WrongDataLength);
+    }
+
+    #[test]
+    fn bytes_corrupted() {
+        let bytes = Board::new(2).unwrap().to_bytes();
+        let corrupt = |i: usize, val: u8| {
+            let mut bytes = bytes.clone();
+            bytes[i] = val;
+            Board::from_bytes(&bytes).unwrap_err()
+        };
+
+        assert_eq!(corrupt(0, 3), DeserializeError::InvalidHeader); // reads a win length of 0
+        assert_eq!(corrupt(2, 0), DeserializeError::InvalidHeader);
+        assert_eq!(corrupt(4, 4), DeserializeError::InvalidHeader);
+        assert_eq!(corrupt(5, 9), DeserializeError::InvalidHeader);
+        assert_eq!(corrupt(6, 3), DeserializeError::InvalidHeader);
+        assert_eq!(corrupt(7, 1), DeserializeError::InvalidHeader);
+
+        // no dimensions, with a win length of 1 and a single cell
+        assert_eq!(Board::from_bytes(&[0, 0, 1, 0, 0, 0, 0]).unwrap_err(), DeserializeError::InvalidHeader);
+    }
+
+    #[test]
+    fn position_parse_valid() {
+        let expected = Position::new(vec![1,2,0], 3, 3).unwrap();
+
+        for input in ["1,2,0", "1 2 0", " 1, 2 ,0\n", "1,,2  0"] {
+            assert_eq!(Position::from_str(input, 3, 3), Ok(expected.clone()));
+        }
+        assert_eq!(expected.to_notation(), "1,2,0");
+        assert_eq!(expected.to_notation().parse(), Ok(expected));
+    }
+
+    #[test]
+    fn position_parse_invalid() {
+        assert_eq!(Position::from_str("1,2", 3, 3), Err(IndexError::OutOfDimension.into()));
+        assert_eq!(Position::from_str("", 2, 3), Err(IndexError::OutOfDimension.into()));
+        assert_eq!(Position::from_str("1,3", 2, 3), Err(IndexError::OutOfBounds.into()));
+        assert!(matches!(Position::from_str("1,x", 2, 3), Err(ParseError::InvalidNumber(_))));
+        assert!(matches!(Position::from_str("1,-1", 2, 3), Err(ParseError::InvalidNumber(_))));
+        assert!(matches!("256".parse::<Position>(), Err(ParseError::InvalidNumber(_))));
+    }
+
+    #[test]
+    fn game_record_replay() {
+        let mut game = Game::new(2, 2);
+        for pos in [[0,0], [1,1], [2,0], [1,0], [0,2], [0,1]] {
+            game.play(&pos).unwrap();
+        }
+        game.undo_move().unwrap();
+
+        let record = GameRecord::record_from_game(&game);
+        let replayed = record.replay().unwrap();
+
+        assert_eq!(record.moves.len(), 5);
+        assert_eq!(record.moves[1], (2, vec![1,1]));
+        assert_eq!(replayed.board().data, game.board().data);
+        assert_eq!(replayed.current_player(), game.current_player());
+    }
+
+    #[test]
+    fn game_record_replay_gravity() {
+        let mut game = Game::with_board(Board::new_with_gravity(3, 2), 2);
+        for pos in [[0,0,0], [0,0,1], [1,1,0], [0,0,2], [2,2,0]] {
+            game.play(&pos).unwrap();
+        }
+
+        let record = GameRecord::record_from_game(&game);
+        let replayed = record.replay().unwrap();
+
+        assert_eq!(record.gravity_axis, Some(2));
+        assert_eq!(replayed.board().data, game.board().data);
+        assert_eq!(replayed.board().gravity_axis(), Some(2));
+        assert_eq!(replayed.board().drop_position(&[0,0]), game.board().drop_position(&[0,0]));
+        assert_eq!(replayed.state(), GameResult::Win(1));
+
+        let notation = record.to_notation();
+        assert!(notation.starts_with("3 2 gravity=2\n"));
+        assert_eq!(GameRecord::from_notation(&notation).unwrap(), record);
+        assert_eq!(GameRecord::from_pgn_like(&record.to_pgn_like()).unwrap(), record);
+    }
+
+    #[test]
+    fn game_record_replay_misere() {
+        let mut game = Game::new_misere(2, 2);
+        for pos in [[0,0], [0,1], [1,1], [0,2], [2,2]] {
+            game.play(&pos).unwrap();
+        }
+        assert_eq!(game.state(), GameResult::Loss(1));
+
+        let record = GameRecord::record_from_game(&game);
+        let replayed = record.replay().unwrap();
+        let pgn = record.to_pgn_like();
+
+        assert_eq!(replayed.state(), GameResult::Loss(1));
+        assert_eq!(replayed.mode(), GameMode::Misere);
+        assert!(pgn.starts_with("[Dimension \"2\"] [Players \"2\"] [Result \"0-1\"] [Mode \"misere\"]\n"));
+        assert_eq!(GameRecord::from_pgn_like(&pgn).unwrap(), record);
+    }
+
+    #[test]
+    fn game_record_replay_wild() {
+        let mut game = Game::new_wild(2, 2);
+        for (piece, pos) in [(2, [0,0]), (2, [1,1]), (1, [2,0]), (2, [2,2])] {
+            game.play_wild(piece, &pos).unwrap();
+        }
+        assert_eq!(game.state(), GameResult::Win(2));
+
+        let record = GameRecord::record_from_game(&game);
+        let pgn = record.to_pgn_like();
+
+        assert_eq!(record.moves[0], (2, vec![0,0]));
+        assert_eq!(record.replay().unwrap().board().data, game.board().data);
+        assert_eq!(pgn, "[Dimension \"2\"] [Players \"2\"] [Result \"0-1\"] [Mode \"wild\"]\n1. 2@(0,0) 2@(1,1) 2. 1@(2,0) 2@(2,2)\n");
+        assert_eq!(GameRecord::from_pgn_like(&pgn).unwrap(), record);
+        assert_eq!(GameRecord::from_notation(&record.to_notation()).unwrap(), record);
+    }
+
+    #[test]
+    fn game_record_rect_board() {
+        let mut board = Board::new_rect(&[4, 4]);
+        board.win_length = 3;
+        let mut game = Game::with_board(board, 2);
+        for pos in [[0,0], [3,3], [1,1], [3,2], [2,2]] {
+            game.play(&pos).unwrap();
+        }
+
+        let record = GameRecord::record_from_game(&game);
+        let notation = record.to_notation();
+
+        assert!(notation.starts_with("2 2 sizes=4,4 win_length=3\n"));
+        assert_eq!(GameRecord::from_notation(&notation).unwrap(), record);
+        assert_eq!(GameRecord::from_pgn_like(&record.to_pgn_like()).unwrap(), record);
+        assert_eq!(record.replay().unwrap().state(), GameResult::Win(1));
+
+        let impossible = GameRecord { win_length: 5, ..record };
+        assert_eq!(impossible.replay().unwrap_err(), BuildError::InvalidWinLength.into());
+    }
+
+    #[test]
+    fn game_record_out_of_turn() {
+        let record = GameRecord { moves: vec![(1, vec![0,0]), (1, vec![1,1])], ..GameRecord::new(2, 2) };
+
+        assert_eq!(record.replay().unwrap_err(), PlaceError::InvalidPiece.into());
+    }
+
+    #[test]
+    fn game_record_notation() {
+        let mut game = Game::new(3, 3);
+        for pos in [[0,0,0], [1,2,0], [2,2,1]] {
+            game.play(&pos).unwrap();
+        }
+        let record = GameRecord::record_from_game(&game);
+
+        let notation = record.to_notation();
+        let actual = GameRecord::from_notation(&notation).unwrap();
+
+        assert_eq!(notation, "3 3\n1: 0,0,0\n2: 1,2,0\n3: 2,2,1\n");
+        assert_eq!(actual, record);
+        assert_eq!(actual.replay().unwrap().board().data, game.board().data);
+    }
+
+    #[test]
+    fn game_record_pgn_like() {
+        let mut game = Game::new(2, 2);
+        for pos in [[0,0], [1,1], [2,0], [1,0], [0,2], [0,1]] {
+            game.play(&pos).unwrap();
+        }
+        game.undo_move().unwrap();
+        let record = GameRecord::record_from_game(&game);
+
+        let pgn = record.to_pgn_like();
+        let actual = GameRecord::from_pgn_like(&pgn).unwrap();
+
+        assert_eq!(pgn, "[Dimension \"2\"] [Players \"2\"] [Result \"*\"]\n1. (0,0) (1,1) 2. (2,0) (1,0) 3. (0,2)\n");
+        assert_eq!(actual, record);
+
+        game.play(&[2,2]).unwrap();
+        game.play(&[0,1]).unwrap();
+        let won = GameRecord::record_from_game(&game).to_pgn_like();
+        assert!(won.starts_with("[Dimension \"2\"] [Players \"2\"] [Result \"1-0\"]\n"));
+    }
+
+    #[test]
+    fn game_record_pgn_like_whitespace() {
+        let pgn = "  [ Dimension  \"3\" ]\n\n[Event \"practice\"][Players \"2\"]\n[Result \"1/2-1/2\"]  1.(0, 0,0)\t( 2,2,2 )\n 2 .   (1,1,0)  ";
+        let record = GameRecord::from_pgn_like(pgn).unwrap();
+
+        assert_eq!(record, GameRecord {
+            moves: vec![(1, vec![0,0,0]), (2, vec![2,2,2]), (1, vec![1,1,0])],
+            ..GameRecord::new(3, 2)
+        });
+
+        assert_eq!(GameRecord::from_pgn_like("[Dimension \"2\"] 1. (0,0)"), Err(ParseError::InvalidFormat));
+        assert_eq!(GameRecord::from_pgn_like("[Dimension \"2\"] [Players \"2\"] 1. (0,0"), Err(ParseError::InvalidFormat));
+        assert!(matches!(GameRecord::from_pgn_like("[Dimension \"2\"] [Players \"2\"] x. (0,0)"), Err(ParseError::InvalidNumber(_))));
+    }
+
+    #[test]
+    fn game_record_no_players() {
+        let record = GameRecord::from_notation("2 0\n").unwrap();
+
+        assert_eq!(record.replay().unwrap_err(), PlaceError::InvalidPiece.into());
+        assert!(record.to_pgn_like().contains("[Result \"*\"]"));
+    }
+
+    #[test]
+    fn game_record_bad_notation() {
+        assert_eq!(GameRecord::from_notation(""), Err(ParseError::InvalidFormat));
+        assert_eq!(GameRecord::from_notation("2\n"), Err(ParseError::InvalidFormat));
+        assert_eq!(GameRecord::from_notation("2 2\n1 0,0\n"), Err(ParseError::InvalidFormat));
+        assert!(matches!(GameRecord::from_notation("2 2\n1: 0,x\n"), Err(ParseError::InvalidNumber(_))));
     }
 }
@@ -1,6 +1,6 @@
 use std::io::{self, prelude::*};
 
-use expanded_ttt::Board;
+use expanded_ttt::{Board, Game, GameResult};
 
 fn main() {
     let stdin = io::stdin();
@@ -8,9 +8,9 @@ fn main() {
 
     println!("Welcome, to a wild and unbridled version of tic-tac-toe, played in as many dimensions as you wish!");
 
-    let dimension: u8 = loop {
+    let dimension: u16 = loop {
         print!("\nEnter dimension of game: ");
-        let _ = stdout.flush().unwrap();
+        stdout.flush().unwrap();
 
         let mut input = String::new();
         _ = stdin.read_line(&mut input).unwrap();
@@ -18,13 +18,13 @@ fn main() {
         match input.trim().parse() {
             Ok(val) if val > 0 => break val,
             Ok(_) => println!("Need at least 1 dimension (though that wouldn't be very fun)"),
-            Err(e) => println!("Failed to parse as u8: {e}"),
+            Err(e) => println!("Failed to parse as u16: {e}"),
         }
     };
     
     let num_players: u8 = loop {
         print!("\nEnter number of players: ");
-        let _ = stdout.flush().unwrap();
+        stdout.flush().unwrap();
 
         let mut input = String::new();
         _ = stdin.read_line(&mut input).unwrap();
@@ -37,44 +37,90 @@ fn main() {
         }
     };
 
-    let mut board = Board::new(dimension);
+    // from 3 dimensions up pieces fall down the last axis, like in Connect Four
+    let board = if dimension >= 3 {
+        Board::new_with_gravity(dimension, usize::from(dimension) - 1)
+    } else {
+        Board::new(dimension).expect("dimension is at least 1")
+    };
+    let mut game = Game::with_board(board, num_players);
+
+    #[cfg(feature = "tui")]
+    match expanded_ttt::tui::run(&mut game) {
+        Ok(GameResult::Win(player)) => println!("Player {player}, YOU WIN"),
+        Ok(GameResult::Loss(player)) => println!("Player {player} completed a line and loses"),
+        Ok(GameResult::Draw) => println!("The board is full, it's a draw!"),
+        Ok(GameResult::Ongoing) => println!("Game left unfinished"),
+        Err(e) => println!("Terminal error: {e}"),
+    }
 
-    println!("\nSorry, but for right now you'll have to keep track of the board yourself.");
-    println!("I'll tell you if there's a win, though!\n");
+    #[cfg(not(feature = "tui"))]
+    play_in_terminal(&mut game);
+}
 
-    let mut current_player = 0;
+/// Play by typing positions in, printing the board after every move
+#[cfg(not(feature = "tui"))]
+fn play_in_terminal(game: &mut Game) {
+    use expanded_ttt::{Position, WinResult};
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    println!("\nPlayer 1 is X, player 2 is O, and any other players go by their number.");
+    println!("I'll tell you if there's a win!\n");
 
     loop {
-        println!("{board:?}");
+        println!("{}", show(game.board()));
 
-        let pos: Vec<u8> = loop {
-            print!("\nEnter position to place piece: ");
-            let _ = stdout.flush().unwrap();
+        let pos = loop {
+            print!("\nPlayer {}, enter position to place piece: ", game.current_player());
+            stdout.flush().unwrap();
 
             let mut input = String::new();
             let _ = stdin.read_line(&mut input).unwrap();
 
-            let parsed = input.trim()
-                .split([' ', ','])
-                .map(|s| s.replace([' ', ',', '_'], ""))
-                .filter(|s| !s.is_empty())
-                .map(|s| s.parse::<u8>());
-            
-            break parsed
-                .map(|n| n.unwrap())
-                .collect()
+            let board = game.board();
+            match Position::from_str(&input, board.dimension, board.size()) {
+                Ok(pos) => break pos,
+                Err(e) => println!("{e}"),
+            }
         };
 
-        match board.place_piece(current_player+1, &pos) {
-            Ok(b) if b==true => break,
-            Ok(_) => {
-                current_player = (current_player+1) % num_players;
+        match game.play(&pos) {
+            Ok(GameResult::Win(player)) => {
+                println!("{}", show(game.board()));
+                if let Ok(WinResult::Win { line, .. }) = game.board().is_win_at(&pos) {
+                    println!("Winning line: {line:?}");
+                }
+                println!("Player {player}, YOU WIN");
+                break;
+            },
+            Ok(GameResult::Loss(player)) => {
+                println!("{}", show(game.board()));
+                println!("Player {player} completed a line and loses");
+                break;
+            },
+            Ok(GameResult::Draw) => {
+                println!("{}", show(game.board()));
+                println!("The board is full, it's a draw!");
+                break;
             },
+            Ok(GameResult::Ongoing) => {},
 
             Err(e) => println!("{e}"),
         }
         let _ = stdout.flush();
     }
+}
+
+/// The board in color, unless colors are turned off with `NO_COLOR` or the `color` feature
+#[cfg(not(feature = "tui"))]
+fn show(board: &Board) -> String {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty());
 
-    println!("Player {}, YOU WIN", current_player+1);
+    if cfg!(feature = "color") && !no_color {
+        board.display_colored()
+    } else {
+        board.to_string()
+    }
 }
@@ -0,0 +1,275 @@
+//! Playing a game between machines over TCP. A [`Server`] holds the game and each player joins
+//! it with a [`Client`]. Every message is a [`GameUpdate`] as JSON, after its length in bytes as
+//! a big endian `u32`, and no longer than [`MAX_MESSAGE_LENGTH`].
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+use crate::{Board, Game, GameResult};
+
+/// The longest message in bytes that will be sent or read, so a peer can't make the other end
+/// allocate as much as a `u32` length allows. Plenty for the board of any game worth playing.
+pub const MAX_MESSAGE_LENGTH: usize = 16 << 20;
+
+/// A message between the server and a client
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum GameUpdate {
+    /// Sent to a client when it joins, with the dimension of the board and the player it is
+    Hello { dimension: u16, player: u8 },
+    /// A move, sent by a client to play it and by the server to everyone once it's played
+    Move { player: u8, position: Vec<u8> },
+    /// Sent to a client whose move couldn't be played, it's still their turn
+    Rejected { reason: String },
+    /// The whole board, sent to everyone when the game ends
+    State { board: Board },
+}
+
+/// Write a message, prefixed with its length
+fn write_update(stream: &mut impl Write, update: &GameUpdate) -> io::Result<()> {
+    let json = serde_json::to_vec(update)?;
+    let length = u32::try_from(json.len())
+        .ok()
+        .filter(|_| json.len() <= MAX_MESSAGE_LENGTH)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "message too long"))?;
+
+    stream.write_all(&length.to_be_bytes())?;
+    stream.write_all(&json)?;
+    stream.flush()
+}
+
+/// Read a message written by [`write_update`], erroring before reading any further if its length
+/// is over [`MAX_MESSAGE_LENGTH`]
+fn read_update(stream: &mut impl Read) -> io::Result<GameUpdate> {
+    let mut length = [0; 4];
+    stream.read_exact(&mut length)?;
+
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_MESSAGE_LENGTH {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "message too long"));
+    }
+
+    let mut json = vec![0; length];
+    stream.read_exact(&mut json)?;
+
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Hosts a game, taking moves from whichever player's turn it is and passing them on to everyone
+#[derive(Debug)]
+pub struct Server {
+    listener: TcpListener,
+    game: Game,
+}
+
+impl Server {
+    /// Listen on `port` of the loopback address for a classic 2D game between 2 players, so only
+    /// players on the same machine can join. Use [`Server::with_game`] to listen elsewhere.
+    pub fn new(port: u16) -> io::Result<Self> {
+        Self::with_game(("127.0.0.1", port), Game::new(2, 2))
+    }
+
+    /// Listen on `addr` to play `game`, with a client for each of its players. Listening on an
+    /// address like `0.0.0.0` lets anyone who can reach the machine join.
+    pub fn with_game(addr: impl ToSocketAddrs, game: Game) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            game,
+        })
+    }
+
+    /// The address the server is listening on, e.g. to find the port when listening on port 0
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Wait for every player to connect, numbered in the order they join, then play the game
+    /// out. Returns the result once the game is over.
+    pub fn run(&mut self) -> io::Result<GameResult> {
+        let dimension = self.game.board().dimension;
+
+        let mut clients = Vec::new();
+        for player in 1..=self.game.num_players() {
+            let (mut stream, _) = self.listener.accept()?;
+            write_update(&mut stream, &GameUpdate::Hello { dimension, player })?;
+            clients.push(stream);
+        }
+
+        loop {
+            let player = self.game.current_player();
+            let stream = &mut clients[usize::from(player) - 1];
+
+            let GameUpdate::Move { position, .. } = read_update(stream)? else {
+                write_update(stream, &GameUpdate::Rejected { reason: String::from("expected a move") })?;
+                continue;
+            };
+
+            let result = match self.game.play(&position) {
+                Ok(result) => result,
+                Err(e) => {
+                    write_update(stream, &GameUpdate::Rejected { reason: e.to_string() })?;
+                    continue;
+                },
+            };
+
+            let update = GameUpdate::Move { player, position };
+            for stream in &mut clients {
+                write_update(stream, &update)?;
+            }
+
+            if result != GameResult::Ongoing {
+                let update = GameUpdate::State { board: self.game.board().clone() };
+                for stream in &mut clients {
+                    write_update(stream, &update)?;
+                }
+
+                return Ok(result);
+            }
+        }
+    }
+}
+
+/// A player's connection to a [`Server`]
+#[derive(Debug)]
+pub struct Client {
+    stream: TcpStream,
+    dimension: u16,
+    player: u8,
+}
+
+impl Client {
+    /// Join the game at `addr`, waiting for the server to say which player this is
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let mut stream = TcpStream::connect(addr)?;
+
+        match read_update(&mut stream)? {
+            GameUpdate::Hello { dimension, player } => Ok(Self { stream, dimension, player }),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "server didn't say hello")),
+        }
+    }
+
+    /// The dimension of the board being played on
+    pub fn dimension(&self) -> u16 {
+        self.dimension
+    }
+
+    /// The player this client plays as
+    pub fn player(&self) -> u8 {
+        self.player
+    }
+
+    /// Play a move, which the server answers with a [`GameUpdate::Move`] to everyone or a
+    /// [`GameUpdate::Rejected`] to this client
+    pub fn send_move(&mut self, pos: &[u8]) -> io::Result<()> {
+        write_update(&mut self.stream, &GameUpdate::Move { player: self.player, position: Vec::from(pos) })
+    }
+
+    /// Wait for the next message from the server
+    pub fn recv_update(&mut self) -> io::Result<GameUpdate> {
+        read_update(&mut self.stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::Player;
+
+    /// A server on a free loopback port, running in the background, and a client for each player
+    fn start(game: Game) -> (thread::JoinHandle<io::Result<GameResult>>, Vec<Client>) {
+        let num_players = game.num_players();
+        let mut server = Server::with_game(("127.0.0.1", 0), game).unwrap();
+        let port = server.local_addr().unwrap().port();
+        let handle = thread::spawn(move || server.run());
+
+        let clients = (0..num_players)
+            .map(|_| Client::connect(("127.0.0.1", port)).unwrap())
+            .collect();
+
+        (handle, clients)
+    }
+
+    #[test]
+    fn framing_round_trip() {
+        let mut buffer = Vec::new();
+        write_update(&mut buffer, &GameUpdate::Move { player: 1, position: vec![1,2,3] }).unwrap();
+
+        let json = br#"{"type":"move","player":1,"position":[1,2,3]}"#;
+        assert_eq!(buffer[..4], (json.len() as u32).to_be_bytes());
+        assert_eq!(&buffer[4..], json);
+
+        let update = read_update(&mut buffer.as_slice()).unwrap();
+        assert!(matches!(update, GameUpdate::Move { player: 1, position } if position == [1,2,3]));
+    }
+
+    #[test]
+    fn framing_too_long() {
+        let mut buffer = (u32::MAX).to_be_bytes().to_vec();
+        buffer.extend_from_slice(b"{}");
+
+        let error = read_update(&mut buffer.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let reason = "x".repeat(MAX_MESSAGE_LENGTH);
+        let error = write_update(&mut Vec::new(), &GameUpdate::Rejected { reason }).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn server_on_loopback() {
+        let server = Server::new(0).unwrap();
+
+        assert!(server.local_addr().unwrap().ip().is_loopback());
+    }
+
+    #[test]
+    fn play_to_a_win() {
+        let (server, mut clients) = start(Game::new(2, 2));
+
+        assert_eq!(clients[0].player(), 1);
+        assert_eq!(clients[1].player(), 2);
+        assert_eq!(clients[1].dimension(), 2);
+
+        let moves = [[0,0], [0,1], [1,0], [1,1], [2,0]];
+        for (i, pos) in moves.iter().enumerate() {
+            clients[i % 2].send_move(pos).unwrap();
+
+            for client in &mut clients {
+                match client.recv_update().unwrap() {
+                    GameUpdate::Move { player, position } => {
+                        assert_eq!(usize::from(player), i % 2 + 1);
+                        assert_eq!(position, pos);
+                    },
+                    update => panic!("expected a move, got {update:?}"),
+                }
+            }
+        }
+
+        for client in &mut clients {
+            match client.recv_update().unwrap() {
+                GameUpdate::State { board } => assert_eq!(board.get(&[2,0]), Ok(Some(Player::P1))),
+                update => panic!("expected the board, got {update:?}"),
+            }
+        }
+        assert_eq!(server.join().unwrap().unwrap(), GameResult::Win(1));
+    }
+
+    #[test]
+    fn bad_move_rejected() {
+        let (_server, mut clients) = start(Game::new(2, 2));
+
+        clients[0].send_move(&[1,1]).unwrap();
+        for client in &mut clients {
+            client.recv_update().unwrap();
+        }
+
+        clients[1].send_move(&[1,1]).unwrap();
+        assert!(matches!(clients[1].recv_update().unwrap(), GameUpdate::Rejected { .. }));
+
+        // still player 2's turn
+        clients[1].send_move(&[0,0]).unwrap();
+        assert!(matches!(clients[0].recv_update().unwrap(), GameUpdate::Move { player: 2, .. }));
+    }
+}
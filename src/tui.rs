@@ -0,0 +1,393 @@
+//! An interactive terminal interface, drawing one 2D slice of the board at a time with
+//! box-drawing characters and moving around the board with the keyboard
+
+use std::io::{self, Write};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, PrintStyledContent, Stylize};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use crate::{Board, Game, GameResult, Position};
+
+/// The color a player's pieces are drawn in
+fn player_color(player: u8) -> Color {
+    match player {
+        1 => Color::Red,
+        2 => Color::Blue,
+        3 => Color::Green,
+        4 => Color::Yellow,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        _ => Color::White,
+    }
+}
+
+/// What the interface should do after a key press
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Continue,
+    Quit,
+    /// The game is over, with the last move's result
+    GameOver(GameResult),
+}
+
+/// Where the player is looking and what they have typed so far
+#[derive(Debug)]
+struct View {
+    /// The highlighted cell. Its coordinates past the first two pick the slice being shown.
+    cursor: Vec<u8>,
+    /// The axis past the first two that moving between slices changes
+    slice_axis: usize,
+    /// A position being typed in, placed instead of the cursor when it isn't empty
+    input: String,
+    /// The result of the last move, and whether it was a mistake
+    message: Option<(String, bool)>,
+}
+
+impl View {
+    fn new(board: &Board) -> Self {
+        Self {
+            cursor: vec![0; board.sizes().len()],
+            slice_axis: 2,
+            input: String::new(),
+            message: None,
+        }
+    }
+
+    /// Move the cursor along an axis, staying on the board
+    fn move_cursor(&mut self, board: &Board, axis: usize, forward: bool) {
+        let Some(val) = self.cursor.get_mut(axis) else {
+            return;
+        };
+
+        if forward {
+            *val = (*val + 1).min(board.sizes()[axis] - 1);
+        } else {
+            *val = val.saturating_sub(1);
+        }
+    }
+
+    /// Pick the next axis past the first two to move between slices along
+    fn next_slice_axis(&mut self) {
+        if self.cursor.len() > 2 {
+            self.slice_axis = (self.slice_axis - 1) % (self.cursor.len() - 2) + 2;
+        }
+    }
+
+    /// Play the typed position, or the cursor if nothing is typed
+    fn play(&mut self, game: &mut Game) -> Action {
+        let pos = if self.input.trim().is_empty() {
+            Ok(self.cursor.clone())
+        } else {
+            let board = game.board();
+            Position::from_str(&self.input, board.dimension, board.size()).map(Vec::from)
+        };
+
+        let pos = match pos {
+            Ok(pos) => pos,
+            Err(e) => {
+                self.message = Some((e.to_string(), true));
+                return Action::Continue;
+            },
+        };
+
+        let player = game.current_player();
+        match game.play(&pos) {
+            Ok(state) => {
+                self.input.clear();
+                self.cursor = pos;
+
+                let (message, action) = match state {
+                    GameResult::Ongoing => (format!("Player {player} played"), Action::Continue),
+                    GameResult::Win(winner) => (format!("Player {winner}, YOU WIN"), Action::GameOver(state)),
+                    GameResult::Loss(loser) => (format!("Player {loser} completed a line and loses"), Action::GameOver(state)),
+                    GameResult::Draw => (String::from("The board is full, it's a draw!"), Action::GameOver(state)),
+                };
+                self.message = Some((message, false));
+
+                action
+            },
+            Err(e) => {
+                self.message = Some((e.to_string(), true));
+                Action::Continue
+            },
+        }
+    }
+
+    fn handle_key(&mut self, game: &mut Game, key: KeyEvent) -> Action {
+        let board = game.board();
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+
+        match key.code {
+            KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Action::Quit,
+            KeyCode::Enter => return self.play(game),
+
+            // shift and up or down moves between slices, otherwise the arrows move around the slice
+            KeyCode::Up if shift => self.move_cursor(board, self.slice_axis, false),
+            KeyCode::Down if shift => self.move_cursor(board, self.slice_axis, true),
+            KeyCode::Left | KeyCode::Right if shift => self.next_slice_axis(),
+            KeyCode::Tab => self.next_slice_axis(),
+            KeyCode::Left => self.move_cursor(board, 0, false),
+            KeyCode::Right => self.move_cursor(board, 0, true),
+            KeyCode::Up => self.move_cursor(board, 1, false),
+            KeyCode::Down => self.move_cursor(board, 1, true),
+
+            KeyCode::Char(c) if c.is_ascii_digit() || c == ',' || c == ' ' => self.input.push(c),
+            KeyCode::Backspace => _ = self.input.pop(),
+            _ => {},
+        }
+
+        Action::Continue
+    }
+
+    /// The position of a cell in the slice being shown
+    fn slice_position(&self, x: u8, y: u8) -> Vec<u8> {
+        let mut pos = self.cursor.clone();
+        if let Some(val) = pos.get_mut(0) {
+            *val = x;
+        }
+        if let Some(val) = pos.get_mut(1) {
+            *val = y;
+        }
+        pos
+    }
+
+    /// The header naming the slice being shown, empty for boards with no more than 2 dimensions
+    fn slice_header(&self) -> String {
+        self.cursor.iter()
+            .enumerate()
+            .skip(2)
+            .map(|(axis, val)| {
+                let selected = if axis == self.slice_axis { "*" } else { "" };
+                format!("{selected}{}={val}", Board::axis_name(axis))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Draw the board, the slice being shown, and the prompt
+    fn draw(&self, out: &mut impl Write, game: &Game, game_over: bool) -> io::Result<()> {
+        let board = game.board();
+        let width = board.sizes().first().copied().unwrap_or(1);
+        let height = board.sizes().get(1).copied().unwrap_or(1);
+
+        queue!(out, Clear(ClearType::All))?;
+        let mut row = 0;
+
+        if game_over {
+            line(out, &mut row, "Game over, press any key to leave")?;
+        } else {
+            line(out, &mut row, &format!("Player {} to move", game.current_player()))?;
+        }
+
+        let header = self.slice_header();
+        if !header.is_empty() {
+            line(out, &mut row, &format!("--- {header} ---"))?;
+        }
+
+        let border = |left: &str, middle: &str, right: &str| {
+            format!("{left}{}{right}", vec!["───"; width.into()].join(middle))
+        };
+
+        line(out, &mut row, &border("┌", "┬", "┐"))?;
+        for y in 0..height {
+            queue!(out, MoveTo(0, row), Print("│"))?;
+            for x in 0..width {
+                let pos = self.slice_position(x, y);
+                let val = board.get(&pos).ok().flatten().map_or(0, u8::from);
+
+                let symbol = match val {
+                    0 => String::from(" "),
+                    val => Board::cell_symbol(val),
+                };
+                let mut cell = format!(" {symbol} ").with(player_color(val));
+                if pos == self.cursor && !game_over {
+                    cell = cell.reverse();
+                }
+
+                queue!(out, PrintStyledContent(cell), Print("│"))?;
+            }
+            row += 1;
+
+            if y + 1 < height {
+                line(out, &mut row, &border("├", "┼", "┤"))?;
+            }
+        }
+        line(out, &mut row, &border("└", "┴", "┘"))?;
+
+        row += 1;
+        if let Some((message, error)) = &self.message {
+            let color = if *error { Color::Red } else { Color::Green };
+            queue!(out, MoveTo(0, row), PrintStyledContent(message.as_str().with(color)))?;
+        }
+        row += 1;
+
+        line(out, &mut row, &format!("> {}", self.input))?;
+        line(out, &mut row, "arrows: move, shift+up/down: change slice, tab: pick slice axis")?;
+        line(out, &mut row, "type a position or press enter to place at the cursor, esc: quit")?;
+
+        out.flush()
+    }
+}
+
+/// Print a line of text at a row, moving on to the next row. Raw mode doesn't return to the
+/// start of the line on a newline, so each line is placed explicitly.
+fn line(out: &mut impl Write, row: &mut u16, text: &str) -> io::Result<()> {
+    queue!(out, MoveTo(0, *row), Print(text))?;
+    *row += 1;
+    Ok(())
+}
+
+/// Run the game in the terminal until it ends or the player quits, returning the game's state
+/// at that point
+pub fn run(game: &mut Game) -> io::Result<GameResult> {
+    let mut out = io::stdout();
+
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide)?;
+
+    let result = play_loop(&mut out, game);
+
+    // put the terminal back even if drawing failed
+    execute!(out, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn play_loop(out: &mut impl Write, game: &mut Game) -> io::Result<GameResult> {
+    let mut view = View::new(game.board());
+
+    loop {
+        view.draw(out, game, false)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+
+        match view.handle_key(game, key) {
+            Action::Continue => {},
+            Action::Quit => return Ok(game.state()),
+            Action::GameOver(state) => {
+                view.draw(out, game, true)?;
+
+                // wait for a key press before leaving the final board
+                while !matches!(event::read()?, Event::Key(key) if key.kind != KeyEventKind::Release) {}
+
+                return Ok(state);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn press(view: &mut View, game: &mut Game, code: KeyCode, modifiers: KeyModifiers) -> Action {
+        view.handle_key(game, KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn cursor_stays_on_board() {
+        let mut game = Game::new(2, 2);
+        let mut view = View::new(game.board());
+
+        for code in [KeyCode::Left, KeyCode::Up, KeyCode::Right, KeyCode::Right, KeyCode::Right] {
+            press(&mut view, &mut game, code, KeyModifiers::NONE);
+        }
+
+        assert_eq!(view.cursor, [2, 0]);
+    }
+
+    #[test]
+    fn change_slice() {
+        let mut game = Game::new(4, 2);
+        let mut view = View::new(game.board());
+
+        press(&mut view, &mut game, KeyCode::Down, KeyModifiers::SHIFT);
+        press(&mut view, &mut game, KeyCode::Tab, KeyModifiers::NONE);
+        press(&mut view, &mut game, KeyCode::Down, KeyModifiers::SHIFT);
+        press(&mut view, &mut game, KeyCode::Down, KeyModifiers::SHIFT);
+
+        assert_eq!(view.cursor, [0, 0, 1, 2]);
+        assert_eq!(view.slice_header(), "z=1, *w=2");
+        assert_eq!(view.slice_position(2, 1), [2, 1, 1, 2]);
+    }
+
+    #[test]
+    fn typed_position_plays() {
+        let mut game = Game::new(2, 2);
+        let mut view = View::new(game.board());
+
+        for c in "1,2".chars() {
+            press(&mut view, &mut game, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        let action = press(&mut view, &mut game, KeyCode::Enter, KeyModifiers::NONE);
+
+        assert_eq!(action, Action::Continue);
+        assert_eq!(game.board().get(&[1, 2]), Ok(Some(Player::P1)));
+        assert_eq!(view.cursor, [1, 2]);
+        assert!(view.input.is_empty());
+    }
+
+    #[test]
+    fn bad_position_shows_error() {
+        let mut game = Game::new(2, 2);
+        let mut view = View::new(game.board());
+
+        for c in "1,7".chars() {
+            press(&mut view, &mut game, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        press(&mut view, &mut game, KeyCode::Enter, KeyModifiers::NONE);
+
+        assert!(view.message.as_ref().is_some_and(|(_, error)| *error));
+        assert_eq!(view.input, "1,7"); // left to be fixed
+        assert_eq!(game.move_count(), 0);
+
+        // placing on an occupied cell is also reported
+        view.input.clear();
+        press(&mut view, &mut game, KeyCode::Enter, KeyModifiers::NONE);
+        press(&mut view, &mut game, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(view.message.as_ref().is_some_and(|(_, error)| *error));
+        assert_eq!(game.move_count(), 1);
+    }
+
+    #[test]
+    fn game_over() {
+        let mut game = Game::new(1, 1);
+        let mut view = View::new(game.board());
+
+        assert_eq!(press(&mut view, &mut game, KeyCode::Enter, KeyModifiers::NONE), Action::Continue);
+        press(&mut view, &mut game, KeyCode::Right, KeyModifiers::NONE);
+        press(&mut view, &mut game, KeyCode::Enter, KeyModifiers::NONE);
+        press(&mut view, &mut game, KeyCode::Right, KeyModifiers::NONE);
+
+        let actual = press(&mut view, &mut game, KeyCode::Enter, KeyModifiers::NONE);
+
+        assert_eq!(actual, Action::GameOver(GameResult::Win(1)));
+        assert_eq!(press(&mut view, &mut game, KeyCode::Esc, KeyModifiers::NONE), Action::Quit);
+    }
+
+    #[test]
+    fn draw_grid() {
+        let mut game = Game::new(2, 2);
+        game.play(&[1, 1]).unwrap();
+        let view = View::new(game.board());
+        let mut out = Vec::new();
+
+        view.draw(&mut out, &game, false).unwrap();
+        let drawn = String::from_utf8_lossy(&out);
+
+        assert!(drawn.contains("┌───┬───┬───┐"));
+        assert!(drawn.contains("└───┴───┴───┘"));
+        assert!(drawn.contains("X"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Bindings for using boards from JavaScript through `wasm-bindgen`. Positions are JavaScript
+//! arrays of numbers, and errors are thrown as strings. Build the package with
+//! `scripts/build-wasm.sh`.
+
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use crate::{Board, GameResult};
+
+/// A board, as seen from JavaScript
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct JsBoard {
+    board: Board,
+}
+
+/// Read a position out of a JavaScript array, checking every coordinate is a whole number that
+/// fits in a `u8`
+fn position_from_js(pos: &JsValue) -> Result<Vec<u8>, JsValue> {
+    if !Array::is_array(pos) {
+        return Err(JsValue::from_str("position must be an array"));
+    }
+
+    Array::from(pos)
+        .iter()
+        .map(|val| {
+            val.as_f64()
+                .filter(|val| val.fract() == 0.0 && (0.0..=255.0).contains(val))
+                .map(|val| val as u8)
+                .ok_or_else(|| JsValue::from_str("coordinates must be whole numbers from 0 to 255"))
+        })
+        .collect()
+}
+
+/// A game result as an object like `{ result: "win", player: 1 }`, with `player` left out for
+/// results without one
+fn result_to_js(result: GameResult) -> JsValue {
+    let (name, player) = match result {
+        GameResult::Ongoing => ("ongoing", None),
+        GameResult::Win(player) => ("win", Some(player)),
+        GameResult::Draw => ("draw", None),
+        GameResult::Loss(player) => ("loss", Some(player)),
+    };
+
+    let object = Object::new();
+    let _ = Reflect::set(&object, &"result".into(), &name.into());
+    if let Some(player) = player {
+        let _ = Reflect::set(&object, &"player".into(), &player.into());
+    }
+
+    object.into()
+}
+
+#[wasm_bindgen]
+impl JsBoard {
+    /// A classic sized board, throwing if `dimension` is 0
+    #[wasm_bindgen(constructor)]
+    pub fn new(dimension: u16) -> Result<JsBoard, JsValue> {
+        let board = Board::new(dimension).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(Self { board })
+    }
+
+    /// Place a piece, returning the result of the game after it, see [`result_to_js`]
+    #[wasm_bindgen(js_name = placePiece)]
+    pub fn place_piece(&mut self, player: u8, pos: &JsValue) -> Result<JsValue, JsValue> {
+        let pos = position_from_js(pos)?;
+
+        self.board.place_piece_number(player, &pos)
+            .map(result_to_js)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// The value at a position, 0 if it's empty
+    pub fn get(&self, pos: &JsValue) -> Result<u8, JsValue> {
+        let pos = position_from_js(pos)?;
+
+        self.board.get(&pos)
+            .map(|val| val.map_or(0, u8::from))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dimension(&self) -> u16 {
+        self.board.dimension
+    }
+
+    /// A copy of every cell, in the order described by [`Board::data`]
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Uint8Array {
+        Uint8Array::from(self.board.data.as_slice())
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn pos(coords: &[u8]) -> JsValue {
+        coords.iter().map(|val| JsValue::from(*val)).collect::<Array>().into()
+    }
+
+    fn result_name(result: &JsValue) -> String {
+        Reflect::get(result, &"result".into()).unwrap().as_string().unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn place_and_get() {
+        let mut board = JsBoard::new(2).unwrap();
+
+        let result = board.place_piece(1, &pos(&[1, 2])).unwrap();
+
+        assert_eq!(result_name(&result), "ongoing");
+        assert_eq!(board.get(&pos(&[1, 2])).unwrap(), 1);
+        assert_eq!(board.data().to_vec()[7], 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn win_result() {
+        let mut board = JsBoard::new(2).unwrap();
+
+        board.place_piece(2, &pos(&[0, 0])).unwrap();
+        board.place_piece(2, &pos(&[1, 1])).unwrap();
+        let result = board.place_piece(2, &pos(&[2, 2])).unwrap();
+
+        assert_eq!(result_name(&result), "win");
+        assert_eq!(Reflect::get(&result, &"player".into()).unwrap().as_f64(), Some(2.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn bad_positions() {
+        let mut board = JsBoard::new(2).unwrap();
+
+        assert!(board.get(&JsValue::from(3)).is_err());
+        assert!(board.get(&pos(&[0, 3])).is_err());
+        assert!(board.place_piece(1, &Array::of1(&JsValue::from(0.5)).into()).is_err());
+        assert!(JsBoard::new(0).is_err());
+    }
+}
@@ -0,0 +1,34 @@
+/* Checks the C interface works from C. From the repository root:
+ *
+ *     cargo build --release
+ *     cc -Iinclude tests/c/test.c -Ltarget/release -lexpanded_ttt -o target/c_test
+ *     LD_LIBRARY_PATH=target/release target/c_test
+ */
+#include <assert.h>
+#include <stdio.h>
+
+#include "tic_tac_toe_nd.h"
+
+int main(void) {
+    BoardHandle *board = board_new(2);
+    assert(board != NULL);
+
+    uint8_t pos[2] = {1, 2};
+    uint8_t out = 0;
+
+    assert(board_place_piece(board, 1, pos, 2) == FFI_OK);
+    assert(board_get(board, pos, 2, &out) == FFI_OK);
+    assert(out == 1);
+
+    assert(board_place_piece(board, 2, pos, 2) == FFI_OCCUPIED);
+    assert(board_get(board, pos, 1, &out) == FFI_OUT_OF_DIMENSION);
+
+    uint8_t off_board[2] = {0, 3};
+    assert(board_get(board, off_board, 2, &out) == FFI_OUT_OF_BOUNDS);
+
+    assert(board_new(0) == NULL);
+    board_free(board);
+
+    puts("ok");
+    return 0;
+}
@@ -0,0 +1,71 @@
+//! Properties of boards that should hold for any dimension, position and player
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use expanded_ttt::{Board, Error, PlaceError, Player, WinResult};
+use proptest::prelude::*;
+
+/// A dimension from 1 to 4 and a position on a classic sized board of that dimension
+fn board_and_pos() -> impl Strategy<Value = (u16, Vec<u8>)> {
+    (1_u16..=4).prop_flat_map(|dimension| {
+        (Just(dimension), prop::collection::vec(0_u8..3, usize::from(dimension)))
+    })
+}
+
+fn player() -> impl Strategy<Value = Player> {
+    (1_u8..=4).prop_map(|number| Player::new(number).unwrap())
+}
+
+proptest! {
+    #[test]
+    fn fresh_board_is_empty((dimension, pos) in board_and_pos()) {
+        let board = Board::new(dimension).unwrap();
+
+        prop_assert_eq!(board.get(&pos), Ok(None));
+    }
+
+    #[test]
+    fn place_then_get((dimension, pos) in board_and_pos(), player in player()) {
+        let mut board = Board::new(dimension).unwrap();
+
+        board.place_piece(player, &pos).unwrap();
+
+        prop_assert_eq!(board.get(&pos), Ok(Some(player)));
+    }
+
+    #[test]
+    fn no_win_on_fresh_board((dimension, pos) in board_and_pos()) {
+        let board = Board::new(dimension).unwrap();
+
+        prop_assert_eq!(board.is_win_at(&pos), Ok(WinResult::NoWin));
+    }
+
+    #[test]
+    fn mirror_twice_is_identity(
+        (dimension, pos) in board_and_pos(),
+        player in player(),
+        axis in 0_usize..4,
+    ) {
+        let mut board = Board::new(dimension).unwrap();
+        let axis = axis % usize::from(dimension);
+        board.place_piece(player, &pos).unwrap();
+
+        let mirrored = board.mirror(axis).mirror(axis);
+
+        prop_assert_eq!(&mirrored.data, &board.data);
+        prop_assert_eq!(mirrored.zobrist_hash(), board.zobrist_hash());
+    }
+
+    #[test]
+    fn place_twice_is_occupied(
+        (dimension, pos) in board_and_pos(),
+        first in player(),
+        second in player(),
+    ) {
+        let mut board = Board::new(dimension).unwrap();
+
+        board.place_piece(first, &pos).unwrap();
+
+        prop_assert_eq!(board.place_piece(second, &pos), Err(Error::PlaceError(PlaceError::Occupied)));
+    }
+}